@@ -0,0 +1,105 @@
+// Compares the enemy spatial hash with scanning every enemy, for the range queries
+// towers run every frame and the hit checks bullets run every frame
+use bevy::prelude::*;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+// The game is a binary crate, so the module is compiled into the benchmark directly
+#[allow(dead_code)]
+#[path = "../src/enemy/spatial_hash.rs"]
+mod spatial_hash;
+use spatial_hash::*;
+
+const ENEMIES: u32 = 1000;
+const TOWERS: u32 = 50;
+const BULLETS: u32 = 200;
+const TOWER_RANGE: f32 = 175.;
+const COLLISION_RADIUS: f32 = 44.;
+
+// Deterministic spread of positions over a 1920x1080 map
+fn positions(count: u32, seed: u32) -> Vec<Vec3> {
+  (0..count)
+    .map(|i| {
+      let x = (i.wrapping_mul(7919).wrapping_add(seed) % 1920) as f32 - 960.;
+      let y = (i.wrapping_mul(104_729).wrapping_add(seed) % 1080) as f32 - 540.;
+      Vec3::new(x, y, 0.)
+    })
+    .collect()
+}
+
+fn enemies() -> Vec<(Entity, Vec3)> {
+  positions(ENEMIES, 17)
+    .into_iter()
+    .enumerate()
+    .map(|(i, position)| (Entity::from_raw(i as u32), position))
+    .collect()
+}
+
+fn brute_force(enemies: &[(Entity, Vec3)], center: Vec3, radius: f32) -> usize {
+  enemies
+    .iter()
+    .filter(|(_, position)| position.distance(center) <= radius)
+    .count()
+}
+
+fn bench_range_queries(c: &mut Criterion) {
+  let enemies = enemies();
+  let towers = positions(TOWERS, 3);
+  let bullets = positions(BULLETS, 11);
+
+  let mut spatial_hash = SpatialHash::default();
+  for (entity, position) in &enemies {
+    spatial_hash.insert(*entity, *position);
+  }
+
+  let mut group = c.benchmark_group("1000 enemies");
+
+  group.bench_function("tower range scan", |b| {
+    b.iter(|| {
+      towers
+        .iter()
+        .map(|tower| brute_force(&enemies, *tower, TOWER_RANGE))
+        .sum::<usize>()
+    })
+  });
+  group.bench_function("tower range spatial hash", |b| {
+    b.iter(|| {
+      towers
+        .iter()
+        .map(|tower| spatial_hash.query_radius(*tower, TOWER_RANGE).count())
+        .sum::<usize>()
+    })
+  });
+
+  group.bench_function("bullet hits scan", |b| {
+    b.iter(|| {
+      bullets
+        .iter()
+        .map(|bullet| brute_force(&enemies, *bullet, COLLISION_RADIUS))
+        .sum::<usize>()
+    })
+  });
+  group.bench_function("bullet hits spatial hash", |b| {
+    b.iter(|| {
+      bullets
+        .iter()
+        .map(|bullet| spatial_hash.query_radius(*bullet, COLLISION_RADIUS).count())
+        .sum::<usize>()
+    })
+  });
+
+  // The grid is rebuilt every frame, so its cost is part of the comparison
+  group.bench_function("rebuild spatial hash", |b| {
+    b.iter(|| {
+      spatial_hash.clear();
+      for (entity, position) in &enemies {
+        spatial_hash.insert(*entity, *position);
+      }
+      black_box(spatial_hash.len())
+    })
+  });
+
+  group.finish();
+}
+
+criterion_group!(benches, bench_range_queries);
+criterion_main!(benches);
@@ -2,7 +2,7 @@ use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
 use crate::movement::*;
-use crate::{Tile, TowerType};
+use crate::{GameSpeed, Tile, TowerType};
 
 pub struct AssetPlugin;
 
@@ -17,7 +17,8 @@ impl Plugin for AssetPlugin {
 
 // Asset loading
 
-#[derive(Resource)]
+// Headless simulations use the default, empty handles since nothing is drawn
+#[derive(Resource, Default)]
 pub struct GameAssets {
   // Fonts
   pub font: Handle<Font>,
@@ -317,6 +318,7 @@ pub struct AnimationIndices {
 
 fn animate_enemy_sprite(
   time: Res<Time>,
+  game_speed: Res<GameSpeed>,
   mut query: Query<(
     &AnimationIndices,
     &mut AnimationTimer,
@@ -335,7 +337,7 @@ fn animate_enemy_sprite(
     }
 
     // Animate sprite
-    timer.tick(time.delta());
+    timer.tick(game_speed.delta(&time));
 
     if timer.just_finished() {
       if sprite.index == indices.last {
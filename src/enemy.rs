@@ -1,8 +1,17 @@
 mod enemy;
 pub use enemy::*;
 
+mod enemy_ability;
+pub use enemy_ability::*;
+
+mod spatial_hash;
+pub use spatial_hash::*;
+
 mod enemy_type;
 pub use enemy_type::*;
 
 mod wave;
 pub use wave::*;
+
+mod endless;
+pub use endless::*;
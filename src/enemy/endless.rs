@@ -0,0 +1,88 @@
+use rand::rngs::Xoshiro256PlusPlus;
+use rand::seq::IteratorRandom;
+use rand::SeedableRng;
+
+use crate::enemy::*;
+
+// Bounty worth of enemies in the first endless round, and how much it grows every round
+pub const ENDLESS_BASE_BUDGET: usize = 250;
+pub const ENDLESS_BUDGET_PER_ROUND: usize = 50;
+
+// Extra health of every enemy per endless round, 0.1 is 10%
+pub const ENDLESS_HEALTH_PER_ROUND: f32 = 0.1;
+
+// Seconds between two enemies of a group in the first endless round. Later rounds spawn
+// faster, down to the minimum
+const ENDLESS_BASE_INTERVAL: f32 = 1.5;
+const ENDLESS_INTERVAL_PER_ROUND: f32 = 0.05;
+const ENDLESS_MIN_INTERVAL: f32 = 0.5;
+
+// Seconds between the starts of the groups of an endless wave
+const ENDLESS_GROUP_STAGGER: f32 = 4.;
+
+// Endless rounds are counted from 1, the first wave after the scripted ones
+pub fn endless_budget(round: usize) -> usize {
+  ENDLESS_BASE_BUDGET + ENDLESS_BUDGET_PER_ROUND * round.saturating_sub(1)
+}
+
+pub fn endless_health_multiplier(round: usize) -> f32 {
+  1. + ENDLESS_HEALTH_PER_ROUND * round as f32
+}
+
+// An enemy costs its bounty, so stronger enemies take up more of the budget
+fn enemy_costs(enemy_stats: &EnemyTypeStats) -> Vec<(EnemyType, usize)> {
+  let mut costs = enemy_stats
+    .enemy
+    .iter()
+    .map(|(enemy_type, bundle)| (*enemy_type, bundle.enemy.bounty.max(1)))
+    .collect::<Vec<_>>();
+  // The stats are a hash map, sort them so the same seed always picks the same enemies
+  costs.sort_by_key(|(enemy_type, cost)| (*cost, enemy_type.to_string()));
+  costs
+}
+
+// Wave of an endless round. Every round has its own generator, so a round plays out the same
+// way for a seed no matter what happened before it, like after continuing a saved game
+pub fn generate_endless_wave(seed: u64, round: usize, enemy_stats: &EnemyTypeStats) -> Wave {
+  let mut rng =
+    Xoshiro256PlusPlus::seed_from_u64(seed ^ (round as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+  let costs = enemy_costs(enemy_stats);
+
+  // Spend the budget on random enemies it can still afford
+  let mut budget = endless_budget(round);
+  let mut counts: Vec<(EnemyType, usize)> = vec![];
+  while let Some((enemy_type, cost)) = costs
+    .iter()
+    .filter(|(_, cost)| *cost <= budget)
+    .choose(&mut rng)
+  {
+    budget -= cost;
+    match counts.iter_mut().find(|(counted, _)| counted == enemy_type) {
+      Some((_, count)) => *count += 1,
+      None => counts.push((*enemy_type, 1)),
+    }
+  }
+
+  let interval =
+    (ENDLESS_BASE_INTERVAL - ENDLESS_INTERVAL_PER_ROUND * round as f32).max(ENDLESS_MIN_INTERVAL);
+  let groups = counts
+    .into_iter()
+    .enumerate()
+    .map(|(index, (enemy_type, count))| SpawnGroup {
+      enemy_type,
+      count,
+      interval,
+      start_delay: ENDLESS_GROUP_STAGGER * index as f32,
+      path: None,
+    })
+    .collect::<Vec<_>>();
+
+  Wave {
+    enemies: expand_groups(&groups),
+    current: 0,
+  }
+}
+
+#[cfg(test)]
+#[path = "enemy/endless_tests.rs"]
+mod tests;
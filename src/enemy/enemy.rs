@@ -1,10 +1,11 @@
 use bevy::prelude::*;
+use bevy::transform::systems::propagate_transforms;
 use serde::{Deserialize, Serialize};
 
 use crate::assets::*;
 use crate::enemy::*;
 use crate::movement::*;
-use crate::{GameState, Map};
+use crate::{GameData, GameplaySet, Map, SimulationEvents, SimulationStep};
 
 pub struct EnemyPlugin;
 
@@ -13,18 +14,49 @@ impl Plugin for EnemyPlugin {
     app
       .register_type::<Enemy>()
       .register_type::<Path>()
-      .add_event::<EnemyDeathEvent>()
+      .add_simulation_event::<EnemyDeathEvent>()
+      .init_resource::<SpatialHash>()
+      // Rebuilt before any tower looks for enemies this step
+      .add_system(
+        update_spatial_hash
+          .after(propagate_transforms)
+          .in_set(SimulationStep::Prepare)
+          .in_schedule(CoreSchedule::FixedUpdate),
+      )
       //.add_startup_system(load_enemy_type_stats)
-      .add_system(despawn_enemy_on_death.in_set(OnUpdate(GameState::Gameplay)));
+      .add_system(
+        despawn_enemy_on_death
+          .in_set(GameplaySet::Simulation)
+          .in_schedule(CoreSchedule::FixedUpdate),
+      );
   }
 }
 
-pub struct EnemyDeathEvent;
+// Sent when an enemy is killed by a tower, not when it reaches the base
+pub struct EnemyDeathEvent {
+  pub entity: Entity,
+  pub enemy_type: EnemyType,
+  pub position: Vec3,
+  // Tower that dealt the final hit
+  pub killer: Option<Entity>,
+  pub bounty: usize,
+}
+
+// Money paid for enemies that don't set a bounty in their stats
+pub const DEFAULT_BOUNTY: usize = 10;
+
+fn default_bounty() -> usize {
+  DEFAULT_BOUNTY
+}
 
 #[derive(Bundle, Debug, Serialize, Deserialize, Clone)]
 pub struct EnemyBundle {
   pub enemy_type: EnemyType,
   pub enemy: Enemy,
+  #[serde(default)]
+  pub abilities: EnemyAbilities,
+  #[serde(default)]
+  pub status_effects: StatusEffects,
   pub movement: Movement,
   pub animation_indices: AnimationIndices,
   pub animation_timer: AnimationTimer,
@@ -37,13 +69,15 @@ impl Default for EnemyBundle {
     Self {
       enemy_type: EnemyType::Green,
       enemy: Enemy::new(1),
+      abilities: EnemyAbilities::default(),
+      status_effects: StatusEffects::default(),
       movement: Movement {
         speed: 50.,
         ..default()
       },
       animation_indices: AnimationIndices { first: 0, last: 9 },
       animation_timer: AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
-      path: Path { index: 0 },
+      path: Path { id: 0, index: 0 },
       name: Name::new("GreenEnemy"),
     }
   }
@@ -53,20 +87,46 @@ impl Default for EnemyBundle {
 #[reflect(Component)]
 pub struct Enemy {
   pub health: i32,
+  // Regeneration can't heal above this. Defaults to the starting health
+  #[serde(default)]
+  pub max_health: i32,
+  // Money the player gets for killing the enemy
+  #[serde(default = "default_bounty")]
+  pub bounty: usize,
+  // Tower whose bullet hit the enemy last
+  #[serde(skip)]
+  pub last_hit_by: Option<Entity>,
 }
 
 #[derive(Reflect, Component, Default, Clone, Serialize, Debug, Deserialize)]
 #[reflect(Component)]
 pub struct Path {
+  // Which of the map's paths the enemy follows
+  #[serde(default)]
+  pub id: usize,
+  // Next checkpoint on that path
   pub index: usize,
 }
 
 impl Enemy {
   pub fn new(health: i32) -> Self {
-    Self { health }
+    Self {
+      health,
+      max_health: health,
+      bounty: DEFAULT_BOUNTY,
+      last_hit_by: None,
+    }
+  }
+
+  // Tougher enemies for later endless rounds. Rounded to the nearest point, rounding up would
+  // double the health of 1 health enemies right away
+  pub fn scale_health(&mut self, multiplier: f32) {
+    self.health = (self.health as f32 * multiplier).round() as i32;
+    self.max_health = (self.max_health as f32 * multiplier).round() as i32;
   }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_enemy(
   commands: &mut Commands,
   map_path: &Map,
@@ -75,21 +135,66 @@ pub fn spawn_enemy(
   position: Vec3,
   path: Path,
   enemy_stats: &EnemyTypeStats,
+  health_multiplier: f32,
 ) {
+  let mut enemy_bundle = enemy_type.get_enemy(map_path, path, enemy_stats);
+  enemy_bundle.enemy.scale_health(health_multiplier);
   commands
-    .spawn(enemy_type.get_enemy(map_path, path, enemy_stats))
+    .spawn(enemy_bundle)
     .insert(enemy_type.get_sprite_sheet_bundle(assets, position));
 }
 
+fn update_spatial_hash(
+  mut spatial_hash: ResMut<SpatialHash>,
+  enemies: Query<(Entity, &GlobalTransform), With<Enemy>>,
+) {
+  spatial_hash.clear();
+  for (entity, transform) in &enemies {
+    spatial_hash.insert(entity, transform.translation());
+  }
+}
+
 fn despawn_enemy_on_death(
   mut commands: Commands,
-  enemies: Query<(Entity, &mut Enemy)>,
+  enemies: Query<(Entity, &Enemy, &EnemyType, &Path, &Movement, &Transform)>,
   mut death_event_writer: EventWriter<EnemyDeathEvent>,
+  assets: Res<GameAssets>,
+  game_data: Res<GameData>,
+  enemy_type_assets: Res<Assets<EnemyTypeStats>>,
+  waves: Res<Assets<Waves>>,
 ) {
-  for (entity, enemy) in &enemies {
+  let (Some(enemy_stats), Some(waves)) = (
+    enemy_type_assets.get(&game_data.enemy_type_stats),
+    waves.get(&game_data.enemy_waves),
+  )
+    else { return; };
+
+  for (entity, enemy, enemy_type, path, movement, transform) in &enemies {
     if enemy.health <= 0 {
-      death_event_writer.send(EnemyDeathEvent);
+      death_event_writer.send(EnemyDeathEvent {
+        entity,
+        enemy_type: *enemy_type,
+        position: transform.translation,
+        killer: enemy.last_hit_by,
+        bounty: enemy.bounty,
+      });
       commands.entity(entity).despawn_recursive();
+
+      // Layered slimes split into weaker slimes where they died
+      let children = enemy_type.on_death_spawns(enemy_stats);
+      let overflow_damage = EnemyType::split_overflow_damage(-enemy.health, children.len());
+      for (child_type, overflow_damage) in children.iter().zip(overflow_damage) {
+        commands
+          .spawn(child_type.get_split_enemy(
+            path,
+            movement,
+            enemy.last_hit_by,
+            overflow_damage,
+            enemy_stats,
+            waves.health_multiplier(),
+          ))
+          .insert(child_type.get_sprite_sheet_bundle(&assets, transform.translation));
+      }
     }
   }
 }
\ No newline at end of file
@@ -0,0 +1,145 @@
+use super::*;
+
+#[test]
+fn test_armor_reduces_damage() {
+    let mut abilities = EnemyAbilities {
+        armor: 2,
+        ..default()
+    };
+    assert_eq!(abilities.absorb_hit(5, false), 3);
+    assert_eq!(abilities.absorb_hit(1, false), 1);
+}
+
+#[test]
+fn test_base_towers_can_kill_every_armored_enemy() {
+    use crate::{ProjectileEffect, TowerType, TowerTypeStats};
+    use strum::IntoEnumIterator;
+
+    let enemy_stats: EnemyTypeStats =
+        ron::from_str(include_str!("../../../assets/data/stats.enemy_types.ron")).unwrap();
+    let tower_stats: TowerTypeStats =
+        ron::from_str(include_str!("../../../assets/data/stats.tower_stats.ron")).unwrap();
+    let armored = enemy_stats
+        .enemy
+        .values()
+        .filter(|enemy| enemy.abilities.armor > 0)
+        .collect::<Vec<_>>();
+    assert!(!armored.is_empty());
+
+    for enemy in armored {
+        for tower_type in TowerType::iter() {
+            let tower = &tower_stats.tower[&tower_type].tower;
+            let armor_piercing = matches!(tower.projectile, ProjectileEffect::ArmorPiercing);
+            let mut abilities = enemy.abilities.clone();
+            let mut health = enemy.enemy.health;
+            let max_hits = health;
+            // One extra hit for the shield
+            for _ in 0..=max_hits {
+                health -= abilities.absorb_hit(tower.damage, armor_piercing) as i32;
+            }
+            assert!(health <= 0, "{} can't kill {}", tower_type, enemy.enemy_type);
+        }
+    }
+}
+
+#[test]
+fn test_shield_blocks_one_hit() {
+    let mut abilities = EnemyAbilities {
+        armor: 1,
+        shield: true,
+        ..default()
+    };
+    assert_eq!(abilities.absorb_hit(10, false), 0);
+    assert!(!abilities.shield);
+    assert_eq!(abilities.absorb_hit(10, false), 9);
+}
+
+#[test]
+fn test_regeneration_accumulates_partial_health() {
+    let mut regeneration = Regeneration {
+        health_per_second: 1.5,
+        ..default()
+    };
+    assert_eq!(regeneration.tick(0.5), 0);
+    assert_eq!(regeneration.tick(0.5), 1);
+    assert_eq!(regeneration.tick(1.), 2);
+    assert!(regeneration.progress.abs() < 0.001);
+}
+
+#[test]
+fn test_sprint_bursts_after_cooldown() {
+    let mut sprint = Sprint {
+        speed_multiplier: 2.,
+        duration: 1.,
+        cooldown: 3.,
+        ..default()
+    };
+    assert_eq!(sprint.tick(2.), None);
+    assert_eq!(sprint.tick(1.5), Some(2.));
+    assert!(sprint.is_sprinting());
+    assert_eq!(sprint.tick(0.25), None);
+    assert_eq!(sprint.tick(0.5), Some(0.5));
+    assert!(!sprint.is_sprinting());
+}
+
+#[test]
+fn test_abilities_default_when_missing_from_stats() {
+    let abilities: EnemyAbilities = ron::from_str("(armor: 3)").unwrap();
+    assert_eq!(abilities.armor, 3);
+    assert!(!abilities.shield);
+    assert!(abilities.regeneration.is_none());
+    assert!(abilities.sprint.is_none());
+}
+
+#[test]
+fn test_armor_piercing_ignores_armor_but_not_shield() {
+    let mut abilities = EnemyAbilities {
+        armor: 3,
+        shield: true,
+        ..default()
+    };
+    assert_eq!(abilities.absorb_hit(5, true), 0);
+    assert_eq!(abilities.absorb_hit(5, true), 5);
+}
+
+#[test]
+fn test_slow_applies_then_wears_off() {
+    let mut status_effects = StatusEffects::default();
+    status_effects.apply_slow(0.5, 1.);
+    assert_eq!(status_effects.tick_slow(0.1), Some(0.5));
+    assert_eq!(status_effects.tick_slow(0.5), None);
+    assert_eq!(status_effects.tick_slow(0.5), Some(2.));
+    assert!(status_effects.slow.is_none());
+    assert_eq!(status_effects.tick_slow(0.5), None);
+}
+
+#[test]
+fn test_slow_again_refreshes_duration() {
+    let mut status_effects = StatusEffects::default();
+    status_effects.apply_slow(0.5, 1.);
+    status_effects.tick_slow(0.1);
+    status_effects.tick_slow(0.8);
+    status_effects.apply_slow(0.25, 1.);
+
+    let slow = status_effects.slow.as_ref().unwrap();
+    assert_eq!(slow.speed_multiplier, 0.5);
+    assert_eq!(slow.remaining, 1.);
+    assert_eq!(status_effects.tick_slow(0.5), None);
+}
+
+#[test]
+fn test_poison_deals_damage_over_duration() {
+    let tower = Entity::from_raw(7);
+    let mut status_effects = StatusEffects::default();
+    status_effects.apply_poison(2., 3., tower);
+
+    let mut total = 0;
+    for _ in 0..8 {
+        if let Some((damage, source)) = status_effects.tick_poison(0.5) {
+            assert_eq!(source, Some(tower));
+            total += damage;
+        }
+    }
+    assert_eq!(total, 6);
+    assert!(status_effects.poison.is_none());
+}
@@ -0,0 +1,96 @@
+use super::*;
+
+fn enemy_stats() -> EnemyTypeStats {
+    ron::from_str(&std::fs::read_to_string("assets/data/stats.enemy_types.ron").unwrap()).unwrap()
+}
+
+fn wave_cost(wave: &Wave, enemy_stats: &EnemyTypeStats) -> usize {
+    wave.enemies
+        .iter()
+        .map(|enemy| enemy_stats.enemy[&enemy.0].enemy.bounty)
+        .sum()
+}
+
+#[test]
+fn test_budget_grows_with_the_round() {
+    assert_eq!(endless_budget(1), ENDLESS_BASE_BUDGET);
+    assert_eq!(endless_budget(3), ENDLESS_BASE_BUDGET + 2 * ENDLESS_BUDGET_PER_ROUND);
+    assert!(endless_health_multiplier(5) > endless_health_multiplier(1));
+}
+
+#[test]
+fn test_same_seed_and_round_generate_the_same_wave() {
+    let stats = enemy_stats();
+    let wave = generate_endless_wave(3, 4, &stats);
+    assert_eq!(wave.enemies, generate_endless_wave(3, 4, &stats).enemies);
+    assert_ne!(wave.enemies, generate_endless_wave(4, 4, &stats).enemies);
+    assert_ne!(wave.enemies, generate_endless_wave(3, 5, &stats).enemies);
+}
+
+#[test]
+fn test_generated_waves_spend_their_budget() {
+    let stats = enemy_stats();
+    let cheapest = stats.enemy.values().map(|bundle| bundle.enemy.bounty).min().unwrap();
+    for round in 1..20 {
+        let wave = generate_endless_wave(0, round, &stats);
+        let cost = wave_cost(&wave, &stats);
+        assert!(cost <= endless_budget(round), "round {} costs {}", round, cost);
+        assert!(endless_budget(round) - cost < cheapest, "round {} costs {}", round, cost);
+    }
+}
+
+#[test]
+fn test_generated_waves_mix_enemy_types_and_are_valid() {
+    let stats = enemy_stats();
+    let wave = generate_endless_wave(0, 10, &stats);
+    let first = wave.enemies[0].0;
+    assert!(wave.enemies.iter().any(|enemy| enemy.0 != first));
+
+    let waves = Waves { waves: vec![wave], ..Default::default() };
+    assert_eq!(waves.validate(&stats), Ok(()));
+}
+
+#[test]
+fn test_endless_waves_continue_after_the_scripted_ones() {
+    let stats = enemy_stats();
+    let mut waves = Waves {
+        waves: vec![Wave::default(), Wave::default()],
+        current: 1,
+        endless: true,
+        generated: None,
+    };
+    assert!(!waves.is_last_wave());
+    assert_eq!(waves.endless_round(), None);
+    assert_eq!(waves.health_multiplier(), 1.);
+
+    waves.current = 2;
+    waves.generate_endless_wave(0, &stats);
+    assert_eq!(waves.endless_round(), Some(1));
+    assert_eq!(waves.health_multiplier(), endless_health_multiplier(1));
+    assert_eq!(waves.current().unwrap().enemies, generate_endless_wave(0, 1, &stats).enemies);
+}
+
+#[test]
+fn test_scripted_waves_end_without_endless_mode() {
+    let mut waves = Waves {
+        waves: vec![Wave::default()],
+        current: 1,
+        ..Default::default()
+    };
+    waves.generate_endless_wave(0, &enemy_stats());
+    assert!(waves.current().is_none());
+    assert!(waves.is_last_wave());
+}
+
+#[test]
+fn test_scale_health() {
+    let mut enemy = Enemy::new(5);
+    enemy.scale_health(1.3);
+    assert_eq!(enemy.health, 7);
+    assert_eq!(enemy.max_health, 7);
+
+    // The first endless round doesn't double the weakest enemies
+    let mut enemy = Enemy::new(1);
+    enemy.scale_health(endless_health_multiplier(1));
+    assert_eq!(enemy.health, 1);
+}
@@ -0,0 +1,67 @@
+use super::*;
+
+fn spatial_hash() -> SpatialHash {
+    let mut spatial_hash = SpatialHash::new(100.);
+    spatial_hash.insert(Entity::from_raw(0), Vec3::new(10., 10., 0.));
+    spatial_hash.insert(Entity::from_raw(1), Vec3::new(150., 10., 0.));
+    spatial_hash.insert(Entity::from_raw(2), Vec3::new(-60., -20., 0.));
+    spatial_hash.insert(Entity::from_raw(3), Vec3::new(500., 500., 0.));
+    spatial_hash
+}
+
+fn sorted(entities: impl Iterator<Item = (Entity, Vec3)>) -> Vec<Entity> {
+    let mut entities = entities.map(|(entity, _)| entity).collect::<Vec<_>>();
+    entities.sort();
+    entities
+}
+
+#[test]
+fn test_query_radius_across_cells() {
+    let found = sorted(spatial_hash().query_radius(Vec3::new(50., 0., 0.), 120.));
+    assert_eq!(found, vec![Entity::from_raw(0), Entity::from_raw(1), Entity::from_raw(2)]);
+}
+
+#[test]
+fn test_query_radius_excludes_entities_in_corner_of_cell() {
+    let found = sorted(spatial_hash().query_radius(Vec3::new(0., 0., 0.), 50.));
+    assert_eq!(found, vec![Entity::from_raw(0)]);
+}
+
+#[test]
+fn test_query_radius_ignores_z() {
+    let mut spatial_hash = SpatialHash::new(100.);
+    spatial_hash.insert(Entity::from_raw(0), Vec3::new(10., 0., 5.));
+    assert!(spatial_hash.any_within(Vec3::new(0., 0., 0.), 10.));
+}
+
+#[test]
+fn test_clear_empties_the_grid() {
+    let mut spatial_hash = spatial_hash();
+    assert_eq!(spatial_hash.len(), 4);
+    spatial_hash.clear();
+    assert!(spatial_hash.is_empty());
+    assert!(!spatial_hash.any_within(Vec3::new(500., 500., 0.), 10.));
+}
+
+#[test]
+fn test_query_radius_matches_brute_force() {
+    let mut spatial_hash = SpatialHash::new(64.);
+    let positions = (0..200)
+        .map(|i| {
+            let i = i as f32;
+            Vec3::new((i * 37.) % 1000. - 500., (i * 91.) % 700. - 350., 0.)
+        })
+        .collect::<Vec<_>>();
+    for (i, position) in positions.iter().enumerate() {
+        spatial_hash.insert(Entity::from_raw(i as u32), *position);
+    }
+
+    let center = Vec3::new(30., -40., 0.);
+    let expected = positions
+        .iter()
+        .enumerate()
+        .filter(|(_, position)| position.distance(center) <= 150.)
+        .map(|(i, _)| Entity::from_raw(i as u32))
+        .collect::<Vec<_>>();
+    assert_eq!(sorted(spatial_hash.query_radius(center, 150.)), expected);
+}
@@ -4,22 +4,22 @@ use crate::*;
 #[test]
 fn test_enemy_direction_calculation() {
     let map = Map {
-        checkpoints: vec![
+        checkpoints: vec![vec![
             Vec3::new(0., 0., 0.),
             Vec3::new(10., 0., 0.),
             Vec3::new(10., 10., 0.)
-        ],
+        ]],
         ..Default::default()
     };
 
     let mut stats_map = bevy::utils::HashMap::new();
     stats_map.insert(EnemyType::Green, EnemyBundle::default());
-    let stats = EnemyTypeStats { enemy: stats_map };
+    let stats = EnemyTypeStats { enemy: stats_map, ..Default::default() };
 
-    let enemy_at_0 = EnemyType::Green.get_enemy(&map, Path { index: 0 }, &stats);
+    let enemy_at_0 = EnemyType::Green.get_enemy(&map, Path { id: 0, index: 0 }, &stats);
     assert_eq!(enemy_at_0.movement.direction, Vec3::new(10., 0., 0.));
 
-    let enemy_at_1 = EnemyType::Green.get_enemy(&map, Path { index: 1 }, &stats);
+    let enemy_at_1 = EnemyType::Green.get_enemy(&map, Path { id: 0, index: 1 }, &stats);
     assert_eq!(enemy_at_1.movement.direction, Vec3::new(10., 10., 0.));
 }
 
@@ -27,12 +27,183 @@ fn test_enemy_direction_calculation() {
 #[should_panic]
 fn test_get_enemy_out_of_bounds_panic() {
     let mut map = Map::default();
-    map.checkpoints = vec![Vec3::ZERO, Vec3::ONE];
+    map.checkpoints = vec![vec![Vec3::ZERO, Vec3::ONE]];
 
     let mut enemy_map = HashMap::new();
     enemy_map.insert(EnemyType::Green, EnemyBundle::default());
-    let stats = EnemyTypeStats { enemy: enemy_map };
+    let stats = EnemyTypeStats { enemy: enemy_map, ..Default::default() };
 
-    let path = Path { index: 1 };
+    let path = Path { id: 0, index: 1 };
     let _ = EnemyType::Green.get_enemy(&map, path, &stats);
 }
+
+#[test]
+fn test_get_enemy_follows_its_path() {
+    let map = Map {
+        checkpoints: vec![
+            vec![Vec3::ZERO, Vec3::new(10., 0., 0.)],
+            vec![Vec3::ZERO, Vec3::new(0., 10., 0.)],
+        ],
+        ..Default::default()
+    };
+
+    let mut enemy_map = HashMap::new();
+    enemy_map.insert(EnemyType::Green, EnemyBundle::default());
+    let stats = EnemyTypeStats { enemy: enemy_map, ..Default::default() };
+
+    let enemy = EnemyType::Green.get_enemy(&map, Path { id: 1, index: 0 }, &stats);
+    assert_eq!(enemy.movement.direction, Vec3::new(0., 10., 0.));
+    assert_eq!(enemy.path.id, 1);
+}
+
+#[test]
+fn test_get_enemy_max_health_defaults_to_health() {
+    let map = Map {
+        checkpoints: vec![vec![Vec3::ZERO, Vec3::ONE]],
+        ..Default::default()
+    };
+
+    let bundle = EnemyBundle {
+        enemy: ron::from_str("(health: 6)").unwrap(),
+        ..Default::default()
+    };
+    let mut enemy_map = HashMap::new();
+    enemy_map.insert(EnemyType::Orange, bundle);
+    let stats = EnemyTypeStats { enemy: enemy_map, ..Default::default() };
+
+    let enemy = EnemyType::Orange.get_enemy(&map, Path::default(), &stats);
+    assert_eq!(enemy.enemy.health, 6);
+    assert_eq!(enemy.enemy.max_health, 6);
+}
+
+#[test]
+fn test_enemy_type_stats_file_loads_abilities() {
+    let stats: EnemyTypeStats =
+        ron::from_str(include_str!("../../../assets/data/stats.enemy_types.ron")).unwrap();
+    assert_eq!(stats.enemy[&EnemyType::Green].abilities.armor, 0);
+    assert!(stats.enemy[&EnemyType::White].abilities.shield);
+    assert!(stats.enemy[&EnemyType::Pink].abilities.regeneration.is_some());
+    assert!(stats.enemy[&EnemyType::Orange].abilities.sprint.is_some());
+    assert!(!EnemyType::Red.on_death_spawns(&stats).is_empty());
+    assert_eq!(stats.enemy[&EnemyType::Green].enemy.bounty, 10);
+    assert!(stats.enemy[&EnemyType::Red].enemy.bounty > stats.enemy[&EnemyType::Green].enemy.bounty);
+}
+
+#[test]
+fn test_enemy_bounty_defaults_when_missing() {
+    let enemy: Enemy = ron::from_str("(health: 3)").unwrap();
+    assert_eq!(enemy.bounty, DEFAULT_BOUNTY);
+    assert_eq!(Enemy::new(3).bounty, DEFAULT_BOUNTY);
+
+    let enemy: Enemy = ron::from_str("(health: 3, bounty: 40)").unwrap();
+    assert_eq!(enemy.bounty, 40);
+    assert!(enemy.last_hit_by.is_none());
+}
+
+fn split_stats() -> EnemyTypeStats {
+    let mut enemy_map = HashMap::new();
+    enemy_map.insert(EnemyType::Red, EnemyBundle { enemy: Enemy::new(8), ..Default::default() });
+    enemy_map.insert(EnemyType::Blue, EnemyBundle { enemy: Enemy::new(5), ..Default::default() });
+
+    let mut on_death_spawns = HashMap::new();
+    on_death_spawns.insert(EnemyType::Red, vec![EnemyType::Blue, EnemyType::Blue]);
+
+    EnemyTypeStats { enemy: enemy_map, on_death_spawns }
+}
+
+#[test]
+fn test_on_death_spawns() {
+    let stats = split_stats();
+    assert_eq!(EnemyType::Red.on_death_spawns(&stats), &[EnemyType::Blue, EnemyType::Blue]);
+    assert!(EnemyType::Blue.on_death_spawns(&stats).is_empty());
+}
+
+#[test]
+fn test_split_enemy_continues_from_parent() {
+    let stats = split_stats();
+    let path = Path { id: 1, index: 4 };
+    let movement = Movement {
+        direction: Vec3::new(0., -3., 0.),
+        speed: 80.,
+        distance_travelled: 512.,
+    };
+
+    let child = EnemyType::Blue.get_split_enemy(&path, &movement, None, 2, &stats, 1.);
+    assert_eq!(child.enemy.health, 3);
+    assert_eq!(child.enemy.max_health, 5);
+    assert_eq!(child.path.id, 1);
+    assert_eq!(child.path.index, 4);
+    assert_eq!(child.movement.direction, Vec3::new(0., -3., 0.));
+    assert_eq!(child.movement.distance_travelled, 512.);
+    // Children keep the speed of their own type
+    assert_eq!(child.movement.speed, 50.);
+}
+
+#[test]
+fn test_split_overflow_damage_is_shared_between_children() {
+    assert_eq!(EnemyType::split_overflow_damage(5, 2), vec![3, 2]);
+    assert_eq!(EnemyType::split_overflow_damage(4, 2), vec![2, 2]);
+    assert_eq!(EnemyType::split_overflow_damage(1, 3), vec![1, 0, 0]);
+    assert_eq!(EnemyType::split_overflow_damage(0, 2), vec![0, 0]);
+    assert!(EnemyType::split_overflow_damage(3, 0).is_empty());
+}
+
+#[test]
+fn test_split_enemy_children_share_overflow_damage() {
+    let stats = split_stats();
+    let children = EnemyType::Red.on_death_spawns(&stats);
+    let overflow_damage = EnemyType::split_overflow_damage(5, children.len());
+
+    let health = children
+        .iter()
+        .zip(overflow_damage)
+        .map(|(child, overflow_damage)| {
+            child
+                .get_split_enemy(
+                    &Path::default(),
+                    &Movement::default(),
+                    None,
+                    overflow_damage,
+                    &stats,
+                    1.,
+                )
+                .enemy
+                .health
+        })
+        .collect::<Vec<_>>();
+    // Two children of 5 health take 5 overflow damage between them, not 5 each
+    assert_eq!(health, vec![2, 3]);
+}
+
+#[test]
+fn test_split_enemy_is_as_tough_as_its_round() {
+    let stats = split_stats();
+    let child = EnemyType::Blue.get_split_enemy(
+        &Path::default(),
+        &Movement::default(),
+        None,
+        1,
+        &stats,
+        1.4,
+    );
+    assert_eq!(child.enemy.max_health, 7);
+    assert_eq!(child.enemy.health, 6);
+}
+
+#[test]
+fn test_split_enemy_killed_by_overflow_damage_is_credited_to_parents_killer() {
+    let stats = split_stats();
+    let tower = Entity::from_raw(3);
+
+    // Blue children have 5 health, so 6 overflow damage kills one as soon as it spawns
+    let child = EnemyType::Blue.get_split_enemy(
+        &Path::default(),
+        &Movement::default(),
+        Some(tower),
+        6,
+        &stats,
+        1.,
+    );
+    assert!(child.enemy.health <= 0);
+    assert_eq!(child.enemy.last_hit_by, Some(tower));
+}
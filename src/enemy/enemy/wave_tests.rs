@@ -5,10 +5,11 @@ use crate::*;
 fn test_wave_progression() {
     let mut waves = Waves {
         waves: vec![
-            Wave { enemies: vec![(EnemyType::Green, Duration::from_secs(1))], current: 0 },
-            Wave { enemies: vec![(EnemyType::Red, Duration::from_secs(1))], current: 0 },
+            Wave { enemies: vec![WaveEnemy(EnemyType::Green, Duration::from_secs(1), None)], current: 0 },
+            Wave { enemies: vec![WaveEnemy(EnemyType::Red, Duration::from_secs(1), None)], current: 0 },
         ],
         current: 0,
+        ..Default::default()
     };
     waves.current += 1;
     let current_wave = waves.current().unwrap();
@@ -20,6 +21,7 @@ fn test_waves_current_returns_none_when_exhausted() {
     let waves = Waves {
         waves: vec![Wave::default()],
         current: 1,
+        ..Default::default()
     };
     assert!(waves.current().is_none());
 }
@@ -27,9 +29,9 @@ fn test_waves_current_returns_none_when_exhausted() {
 #[test]
 fn test_spawn_index_calculation() {
     let enemies = vec![
-        (EnemyType::Green, Duration::from_secs(1)),
-        (EnemyType::Red, Duration::from_secs(2)),
-        (EnemyType::Blue, Duration::from_secs(3)),
+        WaveEnemy(EnemyType::Green, Duration::from_secs(1), None),
+        WaveEnemy(EnemyType::Red, Duration::from_secs(2), None),
+        WaveEnemy(EnemyType::Blue, Duration::from_secs(3), None),
     ];
     let total = enemies.len();
     let remaining_counts = vec![3, 2, 1];
@@ -44,12 +46,210 @@ fn test_spawn_index_calculation() {
 fn test_wave_state_initialization_from_wave() {
     let wave = Wave {
         enemies: vec![
-            (EnemyType::Green, Duration::from_secs(5)),
-            (EnemyType::Red, Duration::from_secs(2)),
+            WaveEnemy(EnemyType::Green, Duration::from_secs(5), None),
+            WaveEnemy(EnemyType::Red, Duration::from_secs(2), None),
         ],
         current: 0,
     };
     let state = WaveState::from((&wave, wave.enemies.len()));
     assert_eq!(state.remaining, 2);
     assert_eq!(state.enemy_spawn_timer.duration(), Duration::from_secs(5));
-}
\ No newline at end of file
+}
+#[test]
+fn test_is_last_wave() {
+    let mut waves = Waves {
+        waves: vec![Wave::default(), Wave::default()],
+        current: 0,
+        ..Default::default()
+    };
+    assert!(!waves.is_last_wave());
+    waves.current = 1;
+    assert!(waves.is_last_wave());
+    waves.current = 2;
+    assert!(waves.is_last_wave());
+}
+
+#[test]
+fn test_early_call_bonus_proportional_to_skipped_time() {
+    assert_eq!(early_call_bonus(Duration::ZERO), 0);
+    assert_eq!(early_call_bonus(Duration::from_secs(4)), 20);
+    assert_eq!(early_call_bonus(TIME_BETWEEN_WAVES), 50);
+}
+
+#[test]
+fn test_early_call_bonus_rounds_partial_seconds() {
+    assert_eq!(early_call_bonus(Duration::from_millis(1500)), 8);
+    assert_eq!(early_call_bonus(Duration::from_millis(1400)), 7);
+}
+
+#[test]
+fn test_wave_enemy_path_is_optional() {
+    let enemies: Vec<WaveEnemy> =
+        ron::from_str("[(Green, (secs: 1, nanos: 0)), (Red, (secs: 2, nanos: 0), Some(1))]").unwrap();
+    assert_eq!(enemies[0], WaveEnemy(EnemyType::Green, Duration::from_secs(1), None));
+    assert_eq!(enemies[1], WaveEnemy(EnemyType::Red, Duration::from_secs(2), Some(1)));
+}
+
+#[test]
+fn test_choose_path_uses_requested_path() {
+    assert_eq!(choose_path(Some(1), 0, 2), 1);
+    assert_eq!(choose_path(Some(0), 3, 2), 0);
+}
+
+#[test]
+fn test_choose_path_alternates_when_unset_or_missing() {
+    assert_eq!(choose_path(None, 0, 2), 0);
+    assert_eq!(choose_path(None, 1, 2), 1);
+    assert_eq!(choose_path(None, 2, 2), 0);
+    assert_eq!(choose_path(Some(3), 1, 2), 1);
+    assert_eq!(choose_path(None, 5, 1), 0);
+}
+
+fn game_enemy_stats() -> EnemyTypeStats {
+    ron::from_str(&std::fs::read_to_string("assets/data/stats.enemy_types.ron").unwrap()).unwrap()
+}
+
+// Stats of the game with only the given enemy types
+fn enemy_stats(enemy_types: &[EnemyType]) -> EnemyTypeStats {
+    let mut stats = game_enemy_stats();
+    stats.enemy.retain(|enemy_type, _| enemy_types.contains(enemy_type));
+    stats
+}
+
+#[test]
+fn test_game_waves_are_valid() {
+    let waves: Waves = ron::from_str(&std::fs::read_to_string("assets/data/enemy.waves.ron").unwrap()).unwrap();
+    assert_eq!(waves.validate(&game_enemy_stats()), Ok(()));
+}
+
+#[test]
+fn test_validate_rejects_no_waves() {
+    let waves = Waves::default();
+    assert_eq!(waves.validate(&enemy_stats(&[])), Err(vec![WaveError::NoWaves]));
+}
+
+#[test]
+fn test_validate_reports_every_problem_with_its_wave() {
+    let waves = Waves {
+        waves: vec![
+            Wave { enemies: vec![WaveEnemy(EnemyType::Green, Duration::from_secs(1), None)], current: 0 },
+            Wave::default(),
+            Wave {
+                enemies: vec![
+                    WaveEnemy(EnemyType::Green, Duration::from_secs(1), None),
+                    WaveEnemy(EnemyType::Green, Duration::ZERO, None),
+                    WaveEnemy(EnemyType::Purple, Duration::from_secs(1), None),
+                ],
+                current: 0,
+            },
+        ],
+        current: 0,
+        ..Default::default()
+    };
+
+    let errors = waves.validate(&enemy_stats(&[EnemyType::Green])).unwrap_err();
+    assert_eq!(
+        errors,
+        vec![
+            WaveError::EmptyWave { wave: 1 },
+            WaveError::ZeroSpawnDelay { wave: 2, enemy: 1 },
+            WaveError::MissingEnemyStats { wave: 2, enemy: 2, enemy_type: EnemyType::Purple },
+        ]
+    );
+    assert_eq!(errors[2].to_string(), "wave 2, enemy 2 is a Purple enemy, which has no stats");
+}
+
+#[test]
+fn test_wave_state_from_empty_wave() {
+    let state = WaveState::from((&Wave::default(), 0));
+    assert_eq!(state.remaining, 0);
+}
+
+fn group(enemy_type: EnemyType, count: usize, interval: f32, start_delay: f32) -> SpawnGroup {
+    SpawnGroup { enemy_type, count, interval, start_delay, path: None }
+}
+
+#[test]
+fn test_legacy_wave_format_is_still_accepted() {
+    let wave: Wave = ron::from_str(
+        "(enemies: [(Green, (secs: 1, nanos: 0)), (Red, (secs: 2, nanos: 0), Some(1))], current: 0)",
+    )
+    .unwrap();
+    assert_eq!(
+        wave.enemies,
+        vec![
+            WaveEnemy(EnemyType::Green, Duration::from_secs(1), None),
+            WaveEnemy(EnemyType::Red, Duration::from_secs(2), Some(1)),
+        ]
+    );
+}
+
+#[test]
+fn test_spawn_groups_expand_into_enemies() {
+    let wave: Wave = ron::from_str(
+        "(groups: [(type: Green, count: 2, interval: 1), (type: Yellow, count: 2, interval: 2, start_delay: 3, path: Some(1))])",
+    )
+    .unwrap();
+    assert_eq!(
+        wave.enemies,
+        vec![
+            WaveEnemy(EnemyType::Green, Duration::from_secs(1), None),
+            WaveEnemy(EnemyType::Green, Duration::from_secs(2), None),
+            WaveEnemy(EnemyType::Yellow, Duration::from_secs(2), Some(1)),
+            WaveEnemy(EnemyType::Yellow, Duration::from_secs(2), Some(1)),
+        ]
+    );
+}
+
+#[test]
+fn test_concurrent_groups_interleave() {
+    let enemies = expand_groups(&[
+        group(EnemyType::Green, 3, 1., 0.),
+        group(EnemyType::Red, 2, 1., 0.5),
+    ]);
+    let types = enemies.iter().map(|enemy| enemy.0).collect::<Vec<_>>();
+    assert_eq!(
+        types,
+        vec![EnemyType::Green, EnemyType::Red, EnemyType::Green, EnemyType::Red, EnemyType::Green]
+    );
+    assert!(enemies[..4].iter().all(|enemy| enemy.1 == Duration::from_millis(500)));
+}
+
+#[test]
+fn test_simultaneous_spawns_are_a_step_apart() {
+    let enemies = expand_groups(&[
+        group(EnemyType::Green, 2, 1., 0.),
+        group(EnemyType::Red, 2, 1., 0.),
+    ]);
+    assert_eq!(enemies[0], WaveEnemy(EnemyType::Green, SIMULATION_TIMESTEP, None));
+    assert_eq!(enemies[1], WaveEnemy(EnemyType::Red, Duration::from_secs(1) - SIMULATION_TIMESTEP, None));
+    assert_eq!(enemies[2], WaveEnemy(EnemyType::Green, SIMULATION_TIMESTEP, None));
+    let waves = Waves { waves: vec![Wave { enemies, current: 0 }], ..Default::default() };
+    assert_eq!(waves.validate(&enemy_stats(&[EnemyType::Green, EnemyType::Red])), Ok(()));
+}
+
+// The game's waves, one enemy at a time like they were written before spawn groups
+#[test]
+fn test_game_waves_match_their_legacy_definition() {
+    let repeat = |enemy_type, delay: u64, count| {
+        vec![WaveEnemy(enemy_type, Duration::from_millis(delay), None); count]
+    };
+    let legacy = [
+        repeat(EnemyType::Green, 1500, 20),
+        repeat(EnemyType::Green, 1000, 35),
+        [
+            repeat(EnemyType::Green, 1000, 9),
+            repeat(EnemyType::Green, 2000, 1),
+            repeat(EnemyType::Yellow, 2000, 5),
+            repeat(EnemyType::Green, 1000, 15),
+        ]
+        .concat(),
+        repeat(EnemyType::Red, 3000, 20),
+    ];
+
+    let waves: Waves = ron::from_str(&std::fs::read_to_string("assets/data/enemy.waves.ron").unwrap()).unwrap();
+    assert_eq!(waves.waves.len(), legacy.len());
+    for (wave, legacy) in waves.waves.iter().zip(&legacy) {
+        assert_eq!(&wave.enemies, legacy);
+    }
+}
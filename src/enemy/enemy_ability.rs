@@ -0,0 +1,267 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::enemy::*;
+use crate::movement::*;
+use crate::{GameplaySet, TowerDamageEvent};
+
+pub struct EnemyAbilityPlugin;
+
+impl Plugin for EnemyAbilityPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .register_type::<EnemyAbilities>()
+      .register_type::<StatusEffects>()
+      .add_systems(
+        (regenerate_enemies, sprint_enemies, slow_enemies, poison_enemies)
+          .in_set(GameplaySet::Simulation)
+          .in_schedule(CoreSchedule::FixedUpdate),
+      );
+  }
+}
+
+// Traits of an enemy type, read from `stats.enemy_types.ron`. Every trait is optional
+#[derive(Reflect, Component, Debug, Default, Clone, Serialize, Deserialize)]
+#[reflect(Component)]
+#[serde(default)]
+pub struct EnemyAbilities {
+  // Flat reduction of the damage of every bullet
+  pub armor: u32,
+  // Blocks all damage of the next bullet
+  pub shield: bool,
+  pub regeneration: Option<Regeneration>,
+  pub sprint: Option<Sprint>,
+}
+
+#[derive(Reflect, FromReflect, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Regeneration {
+  pub health_per_second: f32,
+  // Health regenerated that doesn't add up to a whole point yet
+  #[serde(default)]
+  pub progress: f32,
+}
+
+// Repeating speed bursts - walk for `cooldown` seconds, then sprint for `duration` seconds
+#[derive(Reflect, FromReflect, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Sprint {
+  pub speed_multiplier: f32,
+  pub duration: f32,
+  pub cooldown: f32,
+  #[serde(default)]
+  pub elapsed: f32,
+  #[serde(default)]
+  pub active: bool,
+}
+
+// Effects towers have put on an enemy
+#[derive(Reflect, Component, Debug, Default, Clone, Serialize, Deserialize)]
+#[reflect(Component)]
+#[serde(default)]
+pub struct StatusEffects {
+  pub slow: Option<Slow>,
+  pub poison: Option<Poison>,
+}
+
+#[derive(Reflect, FromReflect, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Slow {
+  pub speed_multiplier: f32,
+  pub remaining: f32,
+  // Whether the movement speed has been multiplied yet
+  #[serde(default)]
+  pub applied: bool,
+}
+
+#[derive(Reflect, FromReflect, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Poison {
+  pub damage_per_second: f32,
+  pub remaining: f32,
+  // Damage that doesn't add up to a whole point yet
+  #[serde(default)]
+  pub progress: f32,
+  // Tower that poisoned the enemy
+  #[serde(skip)]
+  pub source: Option<Entity>,
+}
+
+impl EnemyAbilities {
+  // Damage a bullet deals after the shield and armor. Armor never blocks a hit completely,
+  // otherwise towers without armor piercing couldn't kill armored enemies at all
+  pub fn absorb_hit(&mut self, damage: u32, armor_piercing: bool) -> u32 {
+    if self.shield {
+      self.shield = false;
+      return 0;
+    }
+
+    if armor_piercing {
+      return damage;
+    }
+    damage.saturating_sub(self.armor).max(1)
+  }
+}
+
+impl StatusEffects {
+  // Slowing an enemy that is already slowed only refreshes the duration
+  pub fn apply_slow(&mut self, speed_multiplier: f32, duration: f32) {
+    match &mut self.slow {
+      Some(slow) => slow.remaining = slow.remaining.max(duration),
+      None => {
+        self.slow = Some(Slow {
+          speed_multiplier,
+          remaining: duration,
+          applied: false,
+        })
+      }
+    }
+  }
+
+  // Poisoning an enemy again replaces the previous poison
+  pub fn apply_poison(&mut self, damage_per_second: f32, duration: f32, source: Entity) {
+    let progress = self.poison.as_ref().map_or(0., |poison| poison.progress);
+    self.poison = Some(Poison {
+      damage_per_second,
+      remaining: duration,
+      progress,
+      source: Some(source),
+    });
+  }
+
+  // Factor to multiply the movement speed with when the slow takes hold or wears off
+  pub fn tick_slow(&mut self, delta_seconds: f32) -> Option<f32> {
+    let slow = self.slow.as_mut()?;
+    if !slow.applied {
+      slow.applied = true;
+      return Some(slow.speed_multiplier);
+    }
+
+    slow.remaining -= delta_seconds;
+    if slow.remaining > 0. {
+      return None;
+    }
+
+    let factor = 1. / slow.speed_multiplier;
+    self.slow = None;
+    Some(factor)
+  }
+
+  // Whole health points of poison damage dealt during this tick, and the tower that dealt them
+  pub fn tick_poison(&mut self, delta_seconds: f32) -> Option<(u32, Option<Entity>)> {
+    let poison = self.poison.as_mut()?;
+
+    let delta_seconds = delta_seconds.min(poison.remaining);
+    poison.remaining -= delta_seconds;
+    poison.progress += poison.damage_per_second * delta_seconds;
+    let damage = poison.progress.floor();
+    poison.progress -= damage;
+
+    let source = poison.source;
+    if poison.remaining <= 0. {
+      self.poison = None;
+    }
+    Some((damage as u32, source))
+  }
+}
+
+impl Regeneration {
+  // Whole health points regenerated during this tick
+  pub fn tick(&mut self, delta_seconds: f32) -> i32 {
+    self.progress += self.health_per_second * delta_seconds;
+    let health = self.progress.floor();
+    self.progress -= health;
+    health as i32
+  }
+}
+
+impl Sprint {
+  pub fn is_sprinting(&self) -> bool {
+    let period = self.cooldown + self.duration;
+    period > 0. && self.elapsed % period >= self.cooldown
+  }
+
+  // Factor to multiply the movement speed with when a burst starts or ends
+  pub fn tick(&mut self, delta_seconds: f32) -> Option<f32> {
+    self.elapsed += delta_seconds;
+
+    let sprinting = self.is_sprinting();
+    if sprinting == self.active {
+      return None;
+    }
+
+    self.active = sprinting;
+    Some(if sprinting {
+      self.speed_multiplier
+    } else {
+      1. / self.speed_multiplier
+    })
+  }
+}
+
+fn regenerate_enemies(
+  mut enemies: Query<(&mut Enemy, &mut EnemyAbilities)>,
+  fixed_time: Res<FixedTime>,
+) {
+  for (mut enemy, mut abilities) in &mut enemies {
+    let Some(regeneration) = &mut abilities.regeneration
+      else { continue; };
+
+    let health = regeneration.tick(fixed_time.period.as_secs_f32());
+    // Dead enemies stay dead
+    if health > 0 && enemy.health > 0 {
+      enemy.health = (enemy.health + health).min(enemy.max_health);
+    }
+  }
+}
+
+fn sprint_enemies(
+  mut enemies: Query<(&mut Movement, &mut EnemyAbilities)>,
+  fixed_time: Res<FixedTime>,
+) {
+  for (mut movement, mut abilities) in &mut enemies {
+    let Some(sprint) = &mut abilities.sprint
+      else { continue; };
+
+    if let Some(factor) = sprint.tick(fixed_time.period.as_secs_f32()) {
+      movement.speed *= factor;
+    }
+  }
+}
+
+fn slow_enemies(
+  mut enemies: Query<(&mut Movement, &mut StatusEffects)>,
+  fixed_time: Res<FixedTime>,
+) {
+  for (mut movement, mut status_effects) in &mut enemies {
+    if let Some(factor) = status_effects.tick_slow(fixed_time.period.as_secs_f32()) {
+      movement.speed *= factor;
+    }
+  }
+}
+
+fn poison_enemies(
+  mut enemies: Query<(&mut Enemy, &EnemyType, &mut StatusEffects)>,
+  mut damage_writer: EventWriter<TowerDamageEvent>,
+  fixed_time: Res<FixedTime>,
+) {
+  for (mut enemy, enemy_type, mut status_effects) in &mut enemies {
+    let Some((damage, source)) = status_effects.tick_poison(fixed_time.period.as_secs_f32())
+      else { continue; };
+    // Dead enemies stay dead
+    if damage == 0 || enemy.health <= 0 {
+      continue;
+    }
+
+    if let Some(tower) = source {
+      enemy.last_hit_by = Some(tower);
+      damage_writer.send(TowerDamageEvent {
+        tower,
+        enemy_type: *enemy_type,
+        damage: damage.min(enemy.health as u32),
+        direct_hit: false,
+      });
+    }
+    enemy.health -= damage as i32;
+  }
+}
+
+#[cfg(test)]
+#[path = "enemy/ability_tests.rs"]
+mod tests;
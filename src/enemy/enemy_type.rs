@@ -7,8 +7,12 @@ use strum_macros::Display;
 use crate::assets::*;
 use crate::enemy::*;
 use crate::map::*;
+use crate::movement::*;
 
-#[derive(Component, Display, Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, Hash)]
+#[derive(
+  Component, Reflect, FromReflect, Display, Clone, Copy, Debug, PartialEq, Eq, Deserialize,
+  Serialize, Hash,
+)]
 pub enum EnemyType {
   Green,
   Yellow,
@@ -20,17 +24,20 @@ pub enum EnemyType {
   Red,
 }
 
-#[derive(Resource, Debug, Serialize, Deserialize, TypeUuid, Clone)]
+#[derive(Resource, Debug, Default, Serialize, Deserialize, TypeUuid, Clone)]
 #[uuid = "7aad646e-4054-44d7-b138-1fb79f73f9c1"]
 pub struct EnemyTypeStats {
   pub enemy: HashMap<EnemyType, EnemyBundle>,
+  // Enemies that take the place of an enemy of this type when it dies
+  #[serde(default)]
+  pub on_death_spawns: HashMap<EnemyType, Vec<EnemyType>>,
 }
 
 impl EnemyType {
   pub fn get_enemy(&self, map_path: &Map, path: Path, enemy_stats: &EnemyTypeStats) -> EnemyBundle {
-    let direction = map_path.checkpoints[path.index + 1];
+    let direction = map_path.checkpoints[path.id][path.index + 1];
 
-    let mut enemy_bundle = enemy_stats.enemy[self].clone();
+    let mut enemy_bundle = self.get_base_enemy(enemy_stats);
 
     enemy_bundle.path = path;
     enemy_bundle.movement.direction = direction;
@@ -38,6 +45,61 @@ impl EnemyType {
     enemy_bundle
   }
 
+  // Child of a dead enemy, continuing along the path from where its parent died.
+  // Damage that went past the parent's health is carried into the child, and a child killed by
+  // it counts as a kill of the tower that hit the parent last
+  pub fn get_split_enemy(
+    &self,
+    path: &Path,
+    movement: &Movement,
+    last_hit_by: Option<Entity>,
+    overflow_damage: i32,
+    enemy_stats: &EnemyTypeStats,
+    health_multiplier: f32,
+  ) -> EnemyBundle {
+    let mut enemy_bundle = self.get_base_enemy(enemy_stats);
+
+    enemy_bundle.enemy.scale_health(health_multiplier);
+    enemy_bundle.enemy.health -= overflow_damage;
+    enemy_bundle.enemy.last_hit_by = last_hit_by;
+    enemy_bundle.path = path.clone();
+    enemy_bundle.movement.direction = movement.direction;
+    enemy_bundle.movement.distance_travelled = movement.distance_travelled;
+
+    enemy_bundle
+  }
+
+  // Damage that went past a dead enemy's health, shared out between its children. What doesn't
+  // divide evenly is carried over to the first children, one point each
+  pub fn split_overflow_damage(overflow_damage: i32, children: usize) -> Vec<i32> {
+    if children == 0 {
+      return vec![];
+    }
+
+    let count = children as i32;
+    let share = overflow_damage / count;
+    let remainder = overflow_damage % count;
+    (0..count)
+      .map(|index| share + i32::from(index < remainder))
+      .collect()
+  }
+
+  fn get_base_enemy(&self, enemy_stats: &EnemyTypeStats) -> EnemyBundle {
+    let mut enemy_bundle = enemy_stats.enemy[self].clone();
+
+    let enemy = &mut enemy_bundle.enemy;
+    enemy.max_health = enemy.max_health.max(enemy.health);
+
+    enemy_bundle
+  }
+
+  pub fn on_death_spawns<'a>(&self, enemy_stats: &'a EnemyTypeStats) -> &'a [EnemyType] {
+    enemy_stats
+      .on_death_spawns
+      .get(self)
+      .map_or(&[], |spawns| spawns.as_slice())
+  }
+
   pub fn get_sprite_sheet_bundle(&self, assets: &GameAssets, position: Vec3) -> SpriteSheetBundle {
     let texture_atlas_sprite = match self {
       EnemyType::Green => TextureAtlasSprite::new(0),
@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+// Width and height of a grid cell, roughly two tiles
+pub const DEFAULT_CELL_SIZE: f32 = 128.;
+
+// Uniform grid of enemy positions, so range queries only look at nearby enemies
+// instead of every enemy on the map
+#[derive(Resource, Debug, Clone)]
+pub struct SpatialHash {
+  cell_size: f32,
+  cells: HashMap<(i32, i32), Vec<(Entity, Vec3)>>,
+  len: usize,
+}
+
+impl Default for SpatialHash {
+  fn default() -> Self {
+    Self::new(DEFAULT_CELL_SIZE)
+  }
+}
+
+impl SpatialHash {
+  pub fn new(cell_size: f32) -> Self {
+    Self {
+      cell_size,
+      cells: HashMap::default(),
+      len: 0,
+    }
+  }
+
+  // Keeps the allocated cells around, since they are refilled every frame
+  pub fn clear(&mut self) {
+    for cell in self.cells.values_mut() {
+      cell.clear();
+    }
+    self.len = 0;
+  }
+
+  pub fn insert(&mut self, entity: Entity, position: Vec3) {
+    self
+      .cells
+      .entry(self.cell(position))
+      .or_default()
+      .push((entity, position));
+    self.len += 1;
+  }
+
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  fn cell(&self, position: Vec3) -> (i32, i32) {
+    (
+      (position.x / self.cell_size).floor() as i32,
+      (position.y / self.cell_size).floor() as i32,
+    )
+  }
+
+  // Every entity in the cells overlapped by the square around `center`
+  fn candidates(&self, center: Vec3, half_extent: f32) -> impl Iterator<Item = &(Entity, Vec3)> {
+    let (min_x, min_y) = self.cell(center - Vec3::splat(half_extent));
+    let (max_x, max_y) = self.cell(center + Vec3::splat(half_extent));
+
+    (min_x..=max_x)
+      .flat_map(move |x| (min_y..=max_y).map(move |y| (x, y)))
+      .filter_map(|cell| self.cells.get(&cell))
+      .flatten()
+  }
+
+  // Entities within `radius` of `center`, ignoring the z axis
+  pub fn query_radius(&self, center: Vec3, radius: f32) -> impl Iterator<Item = (Entity, Vec3)> + '_ {
+    self
+      .candidates(center, radius)
+      .filter(move |(_, position)| position.truncate().distance(center.truncate()) <= radius)
+      .copied()
+  }
+
+  pub fn any_within(&self, center: Vec3, radius: f32) -> bool {
+    self.query_radius(center, radius).next().is_some()
+  }
+}
+
+#[cfg(test)]
+#[path = "enemy/spatial_hash_tests.rs"]
+mod tests;
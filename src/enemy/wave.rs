@@ -1,38 +1,83 @@
 use bevy::prelude::*;
 use bevy::reflect::TypeUuid;
 use serde::Deserialize;
+use std::error::Error;
+use std::fmt;
 use std::time::Duration;
 
 use crate::assets::*;
 use crate::enemy::*;
 use crate::map::*;
-use crate::{GameData, GameState};
+use crate::{
+  GameData, GameState, GameplaySet, PlayerCommand, SelectedLevel, SimulationEvents, SimulationRng,
+  SIMULATION_TIMESTEP,
+};
 
 pub struct WavePlugin;
 
 impl Plugin for WavePlugin {
   fn build(&self, app: &mut App) {
     app
-      .add_event::<WaveClearedEvent>()
-      .add_system(load_waves.in_schedule(OnExit(GameState::AssetLoading)))
-      .add_system(spawn_waves.in_set(OnUpdate(GameState::Gameplay)));
+      .add_simulation_event::<WaveClearedEvent>()
+      .add_simulation_event::<CallWaveEvent>()
+      .add_simulation_event::<WaveCalledEarlyEvent>()
+      .add_system(load_waves.in_schedule(OnEnter(GameState::Gameplay)))
+      .add_system(call_next_wave.in_set(GameplaySet::Input))
+      .add_system(
+        spawn_waves
+          .in_set(GameplaySet::Simulation)
+          .in_schedule(CoreSchedule::FixedUpdate),
+      )
+      // Enemies spawned by a step only exist after its commands have been applied
+      .add_system(
+        victory
+          .in_base_set(CoreSet::PostUpdate)
+          .run_if(in_state(GameState::Gameplay)),
+      );
   }
 }
 
+// Time between the last enemy of a wave spawning and the next wave starting
+pub const TIME_BETWEEN_WAVES: Duration = Duration::from_secs(10);
+
+// Money per second of countdown skipped by calling the next wave early
+pub const EARLY_CALL_BONUS_PER_SECOND: f32 = 5.;
+
 pub struct WaveClearedEvent {
   pub index: usize,
 }
 
+// Player asked to skip the countdown and start the next wave now
+pub struct CallWaveEvent;
+
+pub struct WaveCalledEarlyEvent {
+  pub bonus: usize,
+}
+
+#[derive(Component)]
+pub struct NextWaveButton;
+
+pub fn early_call_bonus(skipped: Duration) -> usize {
+  (skipped.as_secs_f32() * EARLY_CALL_BONUS_PER_SECOND).round() as usize
+}
+
 #[derive(Resource, Default, Deserialize, TypeUuid)]
 #[uuid = "2ee4097e-4768-40d6-962b-e7ad0b750219"]
 pub struct Waves {
   pub waves: Vec<Wave>,
+  #[serde(default)]
   pub current: usize,
+  // Keep generating waves once the scripted ones run out, set when a level is started
+  #[serde(skip)]
+  pub endless: bool,
+  // Wave of the current endless round
+  #[serde(skip)]
+  pub generated: Option<Wave>,
 }
 
 impl Waves {
   pub fn current(&self) -> Option<&Wave> {
-    self.waves.get(self.current)
+    self.waves.get(self.current).or(self.generated.as_ref())
   }
 
   pub fn advance(
@@ -43,18 +88,213 @@ impl Waves {
       index: self.current,
     });
     self.current += 1;
+    self.generated = None;
     self.current()
   }
+
+  pub fn is_last_wave(&self) -> bool {
+    !self.endless && self.current + 1 >= self.waves.len()
+  }
+
+  // Rounds after the scripted waves, counted from 1. None while the scripted waves last
+  pub fn endless_round(&self) -> Option<usize> {
+    (self.endless && self.current >= self.waves.len()).then(|| self.current + 1 - self.waves.len())
+  }
+
+  // Generate the current wave if it is an endless round
+  pub fn generate_endless_wave(&mut self, seed: u64, enemy_stats: &EnemyTypeStats) {
+    self.generated = self
+      .endless_round()
+      .map(|round| generate_endless_wave(seed, round, enemy_stats));
+  }
+
+  pub fn health_multiplier(&self) -> f32 {
+    self.endless_round().map_or(1., endless_health_multiplier)
+  }
+
+  // Check that every wave can be spawned, before any of them is played
+  pub fn validate(&self, enemy_stats: &EnemyTypeStats) -> Result<(), Vec<WaveError>> {
+    let mut errors = vec![];
+
+    if self.waves.is_empty() {
+      errors.push(WaveError::NoWaves);
+    }
+    for (wave, wave_enemies) in self.waves.iter().enumerate() {
+      if wave_enemies.enemies.is_empty() {
+        errors.push(WaveError::EmptyWave { wave });
+      }
+      for (enemy, WaveEnemy(enemy_type, delay, _)) in wave_enemies.enemies.iter().enumerate() {
+        if delay.is_zero() {
+          errors.push(WaveError::ZeroSpawnDelay { wave, enemy });
+        }
+        if !enemy_stats.enemy.contains_key(enemy_type) {
+          errors.push(WaveError::MissingEnemyStats {
+            wave,
+            enemy,
+            enemy_type: *enemy_type,
+          });
+        }
+      }
+    }
+
+    if errors.is_empty() {
+      Ok(())
+    } else {
+      Err(errors)
+    }
+  }
+}
+
+// Problem in a waves file that would stop the waves from spawning. Waves and enemies are
+// counted from 0, like the comments in the waves files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WaveError {
+  NoWaves,
+  EmptyWave {
+    wave: usize,
+  },
+  ZeroSpawnDelay {
+    wave: usize,
+    enemy: usize,
+  },
+  MissingEnemyStats {
+    wave: usize,
+    enemy: usize,
+    enemy_type: EnemyType,
+  },
+}
+
+impl fmt::Display for WaveError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      WaveError::NoWaves => write!(f, "there are no waves"),
+      WaveError::EmptyWave { wave } => write!(f, "wave {} has no enemies", wave),
+      WaveError::ZeroSpawnDelay { wave, enemy } => {
+        write!(f, "wave {}, enemy {} has no spawn delay", wave, enemy)
+      }
+      WaveError::MissingEnemyStats {
+        wave,
+        enemy,
+        enemy_type,
+      } => write!(
+        f,
+        "wave {}, enemy {} is a {} enemy, which has no stats",
+        wave, enemy, enemy_type
+      ),
+    }
+  }
 }
 
+impl Error for WaveError {}
+
 #[derive(Component, Deserialize)]
 #[derive(Default)]
+#[serde(from = "WaveDefinition")]
 pub struct Wave {
-  pub enemies: Vec<(EnemyType, Duration)>,
+  pub enemies: Vec<WaveEnemy>,
   pub current: usize, // Current enemy
 }
 
+// Enemy type, delay before the next enemy spawns and optionally the path it takes -
+// `(Green, (secs: 1, nanos: 0))` or `(Green, (secs: 1, nanos: 0), Some(1))`
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct WaveEnemy(
+  pub EnemyType,
+  pub Duration,
+  #[serde(default)] pub Option<usize>,
+);
+
+// Enemies of one type spawning at a steady pace. Groups of a wave run side by side -
+// `(type: Green, count: 20, interval: 1.5)` or
+// `(type: Red, count: 5, interval: 2, start_delay: 10, path: Some(1))`
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct SpawnGroup {
+  #[serde(rename = "type")]
+  pub enemy_type: EnemyType,
+  pub count: usize,
+  // Seconds between two enemies of the group
+  #[serde(default)]
+  pub interval: f32,
+  // Seconds after the first spawn of the wave before the group starts
+  #[serde(default)]
+  pub start_delay: f32,
+  #[serde(default)]
+  pub path: Option<usize>,
+}
+
+// A wave as written in the waves files, either every enemy in spawn order or spawn groups.
+// Groups spawn after the listed enemies if a wave has both
+#[derive(Deserialize)]
+struct WaveDefinition {
+  #[serde(default)]
+  enemies: Vec<WaveEnemy>,
+  #[serde(default)]
+  groups: Vec<SpawnGroup>,
+  #[serde(default)]
+  current: usize,
+}
 
+impl From<WaveDefinition> for Wave {
+  fn from(definition: WaveDefinition) -> Self {
+    let mut enemies = definition.enemies;
+    enemies.extend(expand_groups(&definition.groups));
+    Self {
+      enemies,
+      current: definition.current,
+    }
+  }
+}
+
+fn seconds(seconds: f32) -> Duration {
+  // Negative or invalid times start right away
+  Duration::try_from_secs_f32(seconds).unwrap_or_default()
+}
+
+// Merge the groups into one list of enemies in spawn order. Only one enemy spawns per step,
+// so enemies due at the same time are spread over consecutive steps, in group order
+pub fn expand_groups(groups: &[SpawnGroup]) -> Vec<WaveEnemy> {
+  let mut spawns = groups
+    .iter()
+    .flat_map(|group| {
+      let start = seconds(group.start_delay);
+      let interval = seconds(group.interval);
+      (0..group.count).map(move |index| (start + interval * index as u32, group))
+    })
+    .collect::<Vec<_>>();
+  // Stable, so ties keep the group order
+  spawns.sort_by_key(|(time, _)| *time);
+
+  let mut spawn_times = Vec::with_capacity(spawns.len());
+  for (time, _) in &spawns {
+    let time = match spawn_times.last() {
+      Some(previous) => (*time).max(*previous + SIMULATION_TIMESTEP),
+      None => *time,
+    };
+    spawn_times.push(time);
+  }
+
+  spawns
+    .iter()
+    .enumerate()
+    .map(|(index, (_, group))| {
+      let delay = match spawn_times.get(index + 1) {
+        Some(next) => *next - spawn_times[index],
+        // The last enemy waits for the rest of its group, like in the legacy format
+        None => seconds(group.interval).max(SIMULATION_TIMESTEP),
+      };
+      WaveEnemy(group.enemy_type, delay, group.path)
+    })
+    .collect()
+}
+
+// Waves are shared by every level, so a path the map doesn't have falls back to
+// spreading enemies over the map's paths in turn
+pub fn choose_path(path: Option<usize>, enemy_index: usize, path_count: usize) -> usize {
+  match path {
+    Some(path) if path < path_count => path,
+    _ => enemy_index % path_count.max(1),
+  }
+}
 
 #[derive(Resource)]
 pub struct WaveState {
@@ -65,9 +305,15 @@ pub struct WaveState {
 
 impl From<(&Wave, usize)> for WaveState {
   fn from(wave: (&Wave, usize)) -> Self {
+    // An empty wave spawns nothing, its timer is never used
+    let delay = wave
+      .0
+      .enemies
+      .get(wave.0.current)
+      .map_or(Duration::from_millis(1), |enemy| enemy.1);
     Self {
-      wave_spawn_timer: Timer::new(Duration::from_secs(10), TimerMode::Once),
-      enemy_spawn_timer: Timer::new(wave.0.enemies[wave.0.current].1, TimerMode::Repeating),
+      wave_spawn_timer: Timer::new(TIME_BETWEEN_WAVES, TimerMode::Once),
+      enemy_spawn_timer: Timer::new(delay, TimerMode::Repeating),
       remaining: wave.1,
     }
   }
@@ -77,26 +323,42 @@ fn spawn_waves(
   mut commands: Commands,
   assets: Res<GameAssets>, // Tower and enemy assets
   game_data: Res<GameData>,
-  map: Res<Assets<Map>>,
+  map_path: Res<Map>,
   mut waves: ResMut<Assets<Waves>>,
   mut wave_state: ResMut<WaveState>,
   enemy_type_assets: Res<Assets<EnemyTypeStats>>,
-  time: Res<Time>,
+  fixed_time: Res<FixedTime>,
+  rng: Res<SimulationRng>,
   mut wave_cleared_writer: EventWriter<WaveClearedEvent>,
+  mut call_wave_events: EventReader<CallWaveEvent>,
+  mut wave_called_early_writer: EventWriter<WaveCalledEarlyEvent>,
 ) {
-  let Some(map_path) = map.get(&game_data.map)
-    else { return; };
-  let Some(waves) = waves.get_mut(&game_data.enemy_waves)
+  let (Some(waves), Some(enemy_stats)) = (
+    waves.get_mut(&game_data.enemy_waves),
+    enemy_type_assets.get(&game_data.enemy_type_stats),
+  )
     else { return; };
 
+  let wave_called = call_wave_events.iter().count() > 0;
+
   // If all enemies in wave have finished, if button has been pressed
-  // or if in-between waves timer has finished !!!
+  // or if in-between waves timer has finished
   if wave_state.remaining == 0 {
-    wave_state.wave_spawn_timer.tick(time.delta());
-    if !wave_state.wave_spawn_timer.just_finished() {
+    wave_state.wave_spawn_timer.tick(fixed_time.period);
+    let called_early =
+      wave_called && !wave_state.wave_spawn_timer.finished() && !waves.is_last_wave();
+
+    if !wave_state.wave_spawn_timer.just_finished() && !called_early {
       return;
     }
-    if let Some(next_wave) = waves.advance(&mut wave_cleared_writer) {
+    if called_early {
+      wave_called_early_writer.send(WaveCalledEarlyEvent {
+        bonus: early_call_bonus(wave_state.wave_spawn_timer.remaining()),
+      });
+    }
+    waves.advance(&mut wave_cleared_writer);
+    waves.generate_endless_wave(rng.seed, enemy_stats);
+    if let Some(next_wave) = waves.current() {
       wave_state.remaining = next_wave.enemies.len(); // !!!
       commands.insert_resource(WaveState::from((next_wave, next_wave.enemies.len())));
     }
@@ -106,7 +368,7 @@ fn spawn_waves(
     return;
   };
 
-  wave_state.enemy_spawn_timer.tick(time.delta());
+  wave_state.enemy_spawn_timer.tick(fixed_time.period);
   if !wave_state.enemy_spawn_timer.just_finished() {
     return;
   }
@@ -114,17 +376,19 @@ fn spawn_waves(
   let index = current_wave.enemies.len() - wave_state.remaining;
   //println!("Enemy #{}", (current_wave.enemies.len() - wave_state.remaining + 1));
 
-  let Some(enemy_stats) = enemy_type_assets.get(&game_data.enemy_type_stats)
-    else { return; };
-
+  let path_id = choose_path(current_wave.enemies[index].2, index, map_path.path_count());
   spawn_enemy(
     &mut commands,
-    map_path,
+    &map_path,
     current_wave.enemies[index].0,
     &assets,
-    map_path.checkpoints[0],
-    Path { index: 0 },
+    map_path.checkpoints[path_id][0],
+    Path {
+      id: path_id,
+      index: 0,
+    },
     enemy_stats,
+    waves.health_multiplier(),
   );
 
   wave_state.enemy_spawn_timer = Timer::new(current_wave.enemies[index].1, TimerMode::Repeating);
@@ -133,19 +397,59 @@ fn spawn_waves(
   //}
 }
 
-fn load_waves(mut commands: Commands, game_data: Res<GameData>, waves: Res<Assets<Waves>>) {
-  let Some(waves) = waves.get(&game_data.enemy_waves)
+fn load_waves(
+  mut commands: Commands,
+  game_data: Res<GameData>,
+  selected_level: Res<SelectedLevel>,
+  mut waves: ResMut<Assets<Waves>>,
+) {
+  let Some(waves) = waves.get_mut(&game_data.enemy_waves)
     else { return; };
 
-  let num_enemies = waves.waves[0].enemies.len();
+  // Start from the first wave every time a level is (re)started
+  waves.current = 0;
+  waves.endless = selected_level.endless;
+  waves.generated = None;
+
+  let num_enemies = waves.current().map_or(0, |wave| wave.enemies.len());
 
   commands.insert_resource(WaveState {
-    wave_spawn_timer: Timer::new(Duration::from_secs(10), TimerMode::Once),
+    wave_spawn_timer: Timer::new(TIME_BETWEEN_WAVES, TimerMode::Once),
     enemy_spawn_timer: Timer::new(Duration::from_millis(1), TimerMode::Repeating),
     remaining: num_enemies,
   });
 }
 
+fn call_next_wave(
+  keys: Res<Input<KeyCode>>,
+  interactions: Query<&Interaction, (With<NextWaveButton>, Changed<Interaction>)>,
+  mut player_commands: EventWriter<PlayerCommand>,
+) {
+  let button_clicked = interactions
+    .iter()
+    .any(|interaction| matches!(interaction, Interaction::Clicked));
+
+  if keys.just_pressed(KeyCode::Space) || button_clicked {
+    player_commands.send(PlayerCommand::CallWave);
+  }
+}
+
+// The player wins once the last wave has been fully spawned and every enemy is gone
+fn victory(
+  game_data: Res<GameData>,
+  waves: Res<Assets<Waves>>,
+  wave_state: Res<WaveState>,
+  enemies: Query<(), With<Enemy>>,
+  mut game_state: ResMut<NextState<GameState>>,
+) {
+  let Some(waves) = waves.get(&game_data.enemy_waves)
+    else { return; };
+
+  if waves.is_last_wave() && wave_state.remaining == 0 && enemies.is_empty() {
+    game_state.set(GameState::Victory);
+  }
+}
+
 #[cfg(test)]
 #[path = "enemy/wave_tests.rs"]
 mod tests;
\ No newline at end of file
@@ -3,15 +3,21 @@ use bevy_asset_loader::prelude::*;
 
 use crate::{EnemyTypeStats, Map, TowerTypeStats, Upgrades, Waves};
 
-#[derive(Resource)]
-struct EnemyTypeStatsHandle(Handle<EnemyTypeStats>);
-
 #[derive(AssetCollection, Resource)]
 pub struct GameData {
   #[asset(path = "data/stats.enemy_types.ron")]
   pub enemy_type_stats: Handle<EnemyTypeStats>,
-  #[asset(path = "data/level2.map.ron")]
-  pub map: Handle<Map>,
+  // Levels are every `*.map.ron` file in the data folder
+  #[cfg(not(target_arch = "wasm32"))]
+  #[asset(path = "data", collection(typed))]
+  pub levels: Vec<Handle<Map>>,
+  // Folders can't be listed on the web, so there every level has to be added here
+  #[cfg(target_arch = "wasm32")]
+  #[asset(
+    paths("data/level1.map.ron", "data/level2.map.ron", "data/level3.map.ron"),
+    collection(typed)
+  )]
+  pub levels: Vec<Handle<Map>>,
   #[asset(path = "data/stats.tower_stats.ron")]
   pub tower_type_stats: Handle<TowerTypeStats>,
   #[asset(path = "data/tower.upgrades.ron")]
@@ -1,8 +1,17 @@
 mod base;
 pub use base::*;
 
+mod game_speed;
+pub use game_speed::*;
+
 mod gameplay_ui;
 pub use gameplay_ui::*;
 
+mod pause_menu;
+pub use pause_menu::*;
+
 mod player;
 pub use player::*;
+
+mod results;
+pub use results::*;
@@ -1,5 +1,6 @@
 use crate::GameState;
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 pub struct BasePlugin;
 
@@ -7,11 +8,12 @@ impl Plugin for BasePlugin {
   fn build(&self, app: &mut App) {
     app
       .register_type::<Base>()
-      .add_system(spawn_base.in_schedule(OnEnter(GameState::Gameplay)));
+      .add_system(spawn_base.in_schedule(OnEnter(GameState::Gameplay)))
+      .add_system(game_over.in_set(OnUpdate(GameState::Gameplay)));
   }
 }
 
-#[derive(Reflect, Component, Default)]
+#[derive(Reflect, Component, Default, Clone, Serialize, Deserialize)]
 #[reflect(Component)]
 pub struct Base {
   pub health: i32,
@@ -28,7 +30,14 @@ pub fn damage_base(commands: &mut Commands, entity: &Entity, enemy_health: i32,
     base.health -= enemy_health;
   } else {
     base.health = 0;
-    info!("GAME OVER");
+  }
+}
+
+fn game_over(base: Query<&Base, Changed<Base>>, mut game_state: ResMut<NextState<GameState>>) {
+  for base in &base {
+    if base.health <= 0 {
+      game_state.set(GameState::GameOver);
+    }
   }
 }
 
@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::{GameState, GameplayState};
+
+pub struct GameSpeedPlugin;
+
+impl Plugin for GameSpeedPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .register_type::<GameSpeed>()
+      .init_resource::<GameSpeed>()
+      .add_system(reset_game_speed.in_schedule(OnEnter(GameState::Gameplay)))
+      // Not part of the input set, replays can be watched faster too
+      .add_systems((
+        toggle_game_speed
+          .in_set(OnUpdate(GameState::Gameplay))
+          .run_if(in_state(GameplayState::Running)),
+        update_game_speed_ui,
+      ))
+      // Before `CoreSet::FixedUpdate` uses up the accumulated time
+      .add_system(
+        fast_forward
+          .in_base_set(CoreSet::PreUpdate)
+          .run_if(in_state(GameState::Gameplay)),
+      );
+  }
+}
+
+// Multiplier applied to the time the simulation advances by every frame. Faster speeds run
+// more fixed steps rather than longer ones, so they play out the same as 1x
+#[derive(Resource, Reflect, Clone, Copy, Debug, PartialEq)]
+#[reflect(Resource)]
+pub struct GameSpeed {
+  pub multiplier: u32,
+}
+
+impl Default for GameSpeed {
+  fn default() -> Self {
+    Self { multiplier: 1 }
+  }
+}
+
+impl GameSpeed {
+  pub const MAX_MULTIPLIER: u32 = 3;
+
+  // Cycle between 1x, 2x and 3x
+  pub fn toggle(&mut self) {
+    self.multiplier = self.multiplier % Self::MAX_MULTIPLIER + 1;
+  }
+
+  pub fn scale(&self, delta: Duration) -> Duration {
+    delta * self.multiplier
+  }
+
+  pub fn delta(&self, time: &Time) -> Duration {
+    self.scale(time.delta())
+  }
+}
+
+#[derive(Component)]
+pub struct GameSpeedButton;
+
+#[derive(Component)]
+pub struct GameSpeedUI;
+
+fn reset_game_speed(mut game_speed: ResMut<GameSpeed>) {
+  *game_speed = GameSpeed::default();
+}
+
+fn fast_forward(time: Res<Time>, game_speed: Res<GameSpeed>, mut fixed_time: ResMut<FixedTime>) {
+  // The frame's own delta is added by bevy
+  fixed_time.tick(game_speed.scale(time.delta()) - time.delta());
+}
+
+fn toggle_game_speed(
+  keys: Res<Input<KeyCode>>,
+  interactions: Query<&Interaction, (With<GameSpeedButton>, Changed<Interaction>)>,
+  mut game_speed: ResMut<GameSpeed>,
+) {
+  let button_clicked = interactions
+    .iter()
+    .any(|interaction| matches!(interaction, Interaction::Clicked));
+
+  if keys.just_pressed(KeyCode::F) || button_clicked {
+    game_speed.toggle();
+  }
+}
+
+fn update_game_speed_ui(
+  game_speed: Res<GameSpeed>,
+  mut game_speed_ui: Query<&mut Text, With<GameSpeedUI>>,
+) {
+  if !game_speed.is_changed() {
+    return;
+  }
+
+  for mut text in &mut game_speed_ui {
+    *text = Text::from_section(
+      format!("{}x", game_speed.multiplier),
+      text.sections[0].style.clone(),
+    );
+  }
+}
+
+#[cfg(test)]
+#[path = "gameplay_ui/game_speed_tests.rs"]
+mod tests;
@@ -2,7 +2,7 @@ use bevy::prelude::*;
 
 use crate::assets::*;
 use crate::gameplay_ui::*;
-use crate::{GameData, GameState, Waves};
+use crate::{GameData, GameState, NextWaveButton, WaveState, Waves};
 
 #[derive(Component)]
 pub struct GameplayUIRoot;
@@ -16,13 +16,18 @@ pub struct MoneyUI;
 #[derive(Component)]
 pub struct RoundUI;
 
+#[derive(Component)]
+pub struct WaveCountdownUI;
+
 pub struct GameplayUIPlugin;
 
 impl Plugin for GameplayUIPlugin {
   fn build(&self, app: &mut App) {
     app
       .add_system(spawn_gameplay_ui.in_schedule(OnEnter(GameState::Gameplay)))
-      .add_system(update_gameplay_ui.in_set(OnUpdate(GameState::Gameplay)));
+      .add_systems(
+        (update_gameplay_ui, update_wave_countdown_ui).in_set(OnUpdate(GameState::Gameplay)),
+      );
   }
 }
 
@@ -46,7 +51,12 @@ fn update_gameplay_ui(
   *money = Text::from_section(format!("{}", player.money), money.sections[0].style.clone());
   *health = Text::from_section(format!("{}", base.health), health.sections[0].style.clone());
 
-  if waves.current < waves.waves.len() {
+  if waves.endless {
+    *round = Text::from_section(
+      format!("{}/∞", waves.current + 1),
+      round.sections[0].style.clone(),
+    );
+  } else if waves.current < waves.waves.len() {
     *round = Text::from_section(
       format!("{}/{}", waves.current + 1, waves.waves.len()),
       round.sections[0].style.clone(),
@@ -54,6 +64,32 @@ fn update_gameplay_ui(
   }
 }
 
+// Seconds left before the next wave starts, empty while a wave is spawning
+fn update_wave_countdown_ui(
+  game_data: Res<GameData>,
+  waves: Res<Assets<Waves>>,
+  wave_state: Option<Res<WaveState>>,
+  mut countdown_ui: Query<&mut Text, With<WaveCountdownUI>>,
+) {
+  let Some(wave_state) = wave_state
+    else { return; };
+  let Some(waves) = waves.get(&game_data.enemy_waves)
+    else { return; };
+  let Ok(mut countdown) = countdown_ui.get_single_mut()
+    else { return; };
+
+  let counting_down = wave_state.remaining == 0
+    && !wave_state.wave_spawn_timer.finished()
+    && !waves.is_last_wave();
+  let value = if counting_down {
+    format!("{}s", wave_state.wave_spawn_timer.remaining().as_secs_f32().ceil())
+  } else {
+    String::new()
+  };
+
+  *countdown = Text::from_section(value, countdown.sections[0].style.clone());
+}
+
 fn spawn_gameplay_ui(mut commands: Commands, assets: Res<GameAssets>) {
   commands
     .spawn(NodeBundle {
@@ -187,6 +223,122 @@ fn spawn_gameplay_ui(mut commands: Commands, assets: Res<GameAssets>) {
             })
             .insert(RoundUI)
             .insert(Name::new("Round"));
+
+          commands
+            .spawn(TextBundle {
+              style: Style {
+                position_type: PositionType::Absolute,
+                margin: UiRect {
+                  left: Val::Percent(92.5),
+                  top: Val::Percent(6.),
+                  ..default()
+                },
+                ..default()
+              },
+              text: Text::from_section(
+                "",
+                TextStyle {
+                  font: assets.font.clone(),
+                  font_size: 30.,
+                  color: Color::GOLD,
+                },
+              ),
+              ..default()
+            })
+            .insert(WaveCountdownUI)
+            .insert(Name::new("WaveCountdown"));
+
+          commands
+            .spawn(ButtonBundle {
+              style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Px(160.), Val::Px(50.)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                margin: UiRect {
+                  left: Val::Percent(70.),
+                  top: Val::Percent(1.5),
+                  ..default()
+                },
+                ..default()
+              },
+              background_color: BackgroundColor(Color::ORANGE_RED),
+              ..default()
+            })
+            .with_children(|commands| {
+              commands.spawn(TextBundle::from_section(
+                "Next Wave",
+                TextStyle {
+                  font: assets.font.clone(),
+                  font_size: 30.,
+                  color: Color::WHITE,
+                },
+              ));
+            })
+            .insert(NextWaveButton)
+            .insert(Name::new("NextWaveButton"));
+
+          commands
+            .spawn(ButtonBundle {
+              style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Px(50.), Val::Px(50.)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                margin: UiRect {
+                  left: Val::Percent(87.),
+                  top: Val::Percent(1.5),
+                  ..default()
+                },
+                ..default()
+              },
+              background_color: BackgroundColor(Color::ORANGE_RED),
+              ..default()
+            })
+            .with_children(|commands| {
+              commands.spawn(TextBundle::from_section(
+                "II",
+                TextStyle {
+                  font: assets.font.clone(),
+                  font_size: 36.,
+                  color: Color::WHITE,
+                },
+              ));
+            })
+            .insert(PauseButton)
+            .insert(Name::new("PauseButton"));
+
+          commands
+            .spawn(ButtonBundle {
+              style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Px(60.), Val::Px(50.)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                margin: UiRect {
+                  left: Val::Percent(81.),
+                  top: Val::Percent(1.5),
+                  ..default()
+                },
+                ..default()
+              },
+              background_color: BackgroundColor(Color::ORANGE_RED),
+              ..default()
+            })
+            .with_children(|commands| {
+              commands
+                .spawn(TextBundle::from_section(
+                  "1x",
+                  TextStyle {
+                    font: assets.font.clone(),
+                    font_size: 36.,
+                    color: Color::WHITE,
+                  },
+                ))
+                .insert(GameSpeedUI);
+            })
+            .insert(GameSpeedButton)
+            .insert(Name::new("GameSpeedButton"));
         });
     })
     .insert(Name::new("GameplayUI"));
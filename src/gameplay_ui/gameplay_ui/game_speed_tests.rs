@@ -0,0 +1,36 @@
+use super::*;
+
+#[test]
+fn test_game_speed_default() {
+    assert_eq!(GameSpeed::default().multiplier, 1);
+}
+
+#[test]
+fn test_game_speed_toggle_cycles() {
+    let mut game_speed = GameSpeed::default();
+
+    game_speed.toggle();
+    assert_eq!(game_speed.multiplier, 2);
+
+    game_speed.toggle();
+    assert_eq!(game_speed.multiplier, 3);
+
+    game_speed.toggle();
+    assert_eq!(game_speed.multiplier, 1);
+}
+
+#[test]
+fn test_game_speed_scales_delta() {
+    let game_speed = GameSpeed { multiplier: 3 };
+    assert_eq!(game_speed.scale(Duration::from_millis(100)), Duration::from_millis(300));
+}
+
+#[test]
+fn test_game_speed_scaled_timer_finishes_sooner() {
+    let game_speed = GameSpeed { multiplier: 2 };
+    let mut timer = Timer::from_seconds(1.0, TimerMode::Once);
+
+    timer.tick(game_speed.scale(Duration::from_millis(500)));
+
+    assert!(timer.finished());
+}
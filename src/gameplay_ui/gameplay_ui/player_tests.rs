@@ -0,0 +1,18 @@
+use super::*;
+
+#[test]
+fn test_player_new() {
+    let player = Player::new(100);
+    assert_eq!(player.money, 100);
+    assert_eq!(player.money_earned, 0);
+}
+
+#[test]
+fn test_player_earn_tracks_total() {
+    let mut player = Player::new(100);
+    player.earn(10);
+    player.money -= 50;
+    player.earn(101);
+    assert_eq!(player.money, 161);
+    assert_eq!(player.money_earned, 111);
+}
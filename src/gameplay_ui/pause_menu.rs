@@ -0,0 +1,204 @@
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::assets::*;
+use crate::{despawn_gameplay, GameState, GameplayState, SaveButton};
+
+pub struct PauseMenuPlugin;
+
+impl Plugin for PauseMenuPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .add_system(toggle_pause.in_set(OnUpdate(GameState::Gameplay)))
+      .add_system(pause_game.in_schedule(OnEnter(GameplayState::Paused)))
+      .add_system(resume_game.in_schedule(OnExit(GameplayState::Paused)))
+      .add_systems(
+        (resume_button_clicked, restart_button_clicked, quit_button_clicked)
+          .in_set(OnUpdate(GameplayState::Paused)),
+      )
+      // Restarting or quitting from the pause menu throws away the level.
+      // Game over and victory keep it, so the results screen can be shown on top of it
+      .add_systems(
+        (despawn_gameplay, unpause_on_exit)
+          .distributive_run_if(in_state(GameplayState::Paused))
+          .in_schedule(OnExit(GameState::Gameplay)),
+      );
+  }
+}
+
+#[derive(Component)]
+pub struct PauseButton;
+
+#[derive(Component)]
+pub struct PauseMenuUIRoot;
+
+#[derive(Component)]
+pub struct ResumeButton;
+
+#[derive(Component)]
+pub struct RestartButton;
+
+#[derive(Component)]
+pub struct QuitButton;
+
+fn toggle_pause(
+  keys: Res<Input<KeyCode>>,
+  interactions: Query<&Interaction, (With<PauseButton>, Changed<Interaction>)>,
+  gameplay_state: Res<State<GameplayState>>,
+  mut next_gameplay_state: ResMut<NextState<GameplayState>>,
+) {
+  let button_clicked = interactions
+    .iter()
+    .any(|interaction| matches!(interaction, Interaction::Clicked));
+
+  if keys.just_pressed(KeyCode::Escape) || button_clicked {
+    next_gameplay_state.set(match gameplay_state.0 {
+      GameplayState::Running => GameplayState::Paused,
+      GameplayState::Paused => GameplayState::Running,
+    });
+  }
+}
+
+fn pause_game(mut commands: Commands, mut time: ResMut<Time>, assets: Res<GameAssets>) {
+  // Stop every timer and movement step that depends on the time delta
+  time.pause();
+  spawn_pause_menu(&mut commands, &assets);
+}
+
+fn resume_game(
+  mut commands: Commands,
+  mut time: ResMut<Time>,
+  pause_menu_root: Query<Entity, With<PauseMenuUIRoot>>,
+) {
+  time.unpause();
+  for entity in &pause_menu_root {
+    commands.entity(entity).despawn_recursive();
+  }
+}
+
+fn unpause_on_exit(mut next_gameplay_state: ResMut<NextState<GameplayState>>) {
+  next_gameplay_state.set(GameplayState::Running);
+}
+
+fn resume_button_clicked(
+  interactions: Query<&Interaction, (With<ResumeButton>, Changed<Interaction>)>,
+  mut next_gameplay_state: ResMut<NextState<GameplayState>>,
+) {
+  for interaction in &interactions {
+    if matches!(interaction, Interaction::Clicked) {
+      next_gameplay_state.set(GameplayState::Running);
+    }
+  }
+}
+
+fn restart_button_clicked(
+  interactions: Query<&Interaction, (With<RestartButton>, Changed<Interaction>)>,
+  mut game_state: ResMut<NextState<GameState>>,
+) {
+  for interaction in &interactions {
+    if matches!(interaction, Interaction::Clicked) {
+      // Re-entering gameplay loads the selected level again
+      game_state.set(GameState::Gameplay);
+    }
+  }
+}
+
+fn quit_button_clicked(
+  interactions: Query<&Interaction, (With<QuitButton>, Changed<Interaction>)>,
+  mut game_state: ResMut<NextState<GameState>>,
+) {
+  for interaction in &interactions {
+    if matches!(interaction, Interaction::Clicked) {
+      game_state.set(GameState::MainMenu);
+    }
+  }
+}
+
+fn spawn_pause_menu(commands: &mut Commands, assets: &GameAssets) {
+  let text_style = TextStyle {
+    font: assets.font.clone(),
+    font_size: 30.,
+    color: Color::WHITE,
+  };
+
+  commands
+    .spawn(NodeBundle {
+      background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.6)),
+      style: Style {
+        size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+        position_type: PositionType::Absolute,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        flex_direction: FlexDirection::Column,
+        ..default()
+      },
+      // Block clicks from reaching the tower buttons behind the overlay
+      focus_policy: FocusPolicy::Block,
+      z_index: ZIndex::Global(10),
+      ..default()
+    })
+    .insert(PauseMenuUIRoot)
+    .insert(Name::new("PauseMenuUI"))
+    .with_children(|commands| {
+      commands.spawn(
+        TextBundle::from_section(
+          "Paused",
+          TextStyle {
+            font: assets.font.clone(),
+            font_size: 90.,
+            color: Color::CYAN,
+          },
+        )
+        .with_style(Style {
+          margin: UiRect::all(Val::Percent(2.)),
+          ..default()
+        }),
+      );
+
+      commands
+        .spawn(pause_menu_button())
+        .with_children(|commands| {
+          commands.spawn(TextBundle::from_section("Resume", text_style.clone()));
+        })
+        .insert(ResumeButton)
+        .insert(Name::new("ResumeButton"));
+
+      commands
+        .spawn(pause_menu_button())
+        .with_children(|commands| {
+          commands.spawn(TextBundle::from_section("Restart", text_style.clone()));
+        })
+        .insert(RestartButton)
+        .insert(Name::new("RestartButton"));
+
+      commands
+        .spawn(pause_menu_button())
+        .with_children(|commands| {
+          commands.spawn(TextBundle::from_section("Save Game", text_style.clone()));
+        })
+        .insert(SaveButton)
+        .insert(Name::new("SaveButton"));
+
+      commands
+        .spawn(pause_menu_button())
+        .with_children(|commands| {
+          commands.spawn(TextBundle::from_section("Quit to Main Menu", text_style.clone()));
+        })
+        .insert(QuitButton)
+        .insert(Name::new("QuitButton"));
+    });
+}
+
+fn pause_menu_button() -> ButtonBundle {
+  ButtonBundle {
+    style: Style {
+      size: Size::new(Val::Px(300.), Val::Px(60.)),
+      justify_content: JustifyContent::Center,
+      align_items: AlignItems::Center,
+      margin: UiRect::all(Val::Px(10.)),
+      ..default()
+    },
+    background_color: BackgroundColor(Color::ORANGE_RED),
+    ..default()
+  }
+}
@@ -1,6 +1,9 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::{EnemyDeathEvent, GameState, WaveClearedEvent};
+use crate::{
+  EnemyDeathEvent, GameState, GameplaySet, WaveCalledEarlyEvent, WaveClearedEvent,
+};
 
 pub struct PlayerPlugin;
 
@@ -10,20 +13,41 @@ impl Plugin for PlayerPlugin {
       .register_type::<Player>()
       .add_system(spawn_player.in_schedule(OnEnter(GameState::Gameplay)))
       .add_systems(
-        (give_money_on_enemy_death, give_money_on_wave_cleared)
-          .in_set(OnUpdate(GameState::Gameplay)),
+        (
+          give_money_on_enemy_death,
+          give_money_on_wave_cleared,
+          give_money_on_wave_called_early,
+        )
+          .in_set(GameplaySet::Simulation)
+          .in_schedule(CoreSchedule::FixedUpdate),
       );
   }
 }
 
-#[derive(Component, Reflect, Default)]
+#[derive(Component, Reflect, Default, Clone, Serialize, Deserialize)]
 #[reflect(Component)]
 pub struct Player {
   pub money: usize,
+  // Total money received during the run, shown on the results screen
+  pub money_earned: usize,
+}
+
+impl Player {
+  pub fn new(money: usize) -> Self {
+    Self {
+      money,
+      money_earned: 0,
+    }
+  }
+
+  pub fn earn(&mut self, amount: usize) {
+    self.money += amount;
+    self.money_earned += amount;
+  }
 }
 
 fn spawn_player(mut commands: Commands) {
-  commands.spawn((Player { money: 100 }, Name::new("Player")));
+  commands.spawn((Player::new(100), Name::new("Player")));
 }
 
 fn give_money_on_enemy_death(
@@ -31,8 +55,8 @@ fn give_money_on_enemy_death(
   mut death_events: EventReader<EnemyDeathEvent>,
 ) {
   let mut player = player.single_mut();
-  for _ in death_events.iter() {
-    player.money += 10;
+  for death in death_events.iter() {
+    player.earn(death.bounty);
   }
 }
 
@@ -42,6 +66,20 @@ fn give_money_on_wave_cleared(
 ) {
   let mut player = player.single_mut();
   for wave in wave_events.iter() {
-    player.money += wave.index + 101;
+    player.earn(wave.index + 101);
   }
 }
+
+fn give_money_on_wave_called_early(
+  mut player: Query<&mut Player>,
+  mut wave_events: EventReader<WaveCalledEarlyEvent>,
+) {
+  let mut player = player.single_mut();
+  for wave in wave_events.iter() {
+    player.earn(wave.bonus);
+  }
+}
+
+#[cfg(test)]
+#[path = "gameplay_ui/player_tests.rs"]
+mod tests;
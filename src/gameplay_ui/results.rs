@@ -0,0 +1,205 @@
+use bevy::prelude::*;
+
+use crate::assets::*;
+use crate::gameplay_ui::*;
+use crate::{
+  despawn_gameplay, format_damage_breakdown, GameData, GameState, Tower, TowerLifetimeStats,
+  TowerType, Waves,
+};
+
+pub struct ResultsPlugin;
+
+impl Plugin for ResultsPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .add_system(spawn_results_screen.in_schedule(OnEnter(GameState::GameOver)))
+      .add_system(spawn_results_screen.in_schedule(OnEnter(GameState::Victory)))
+      .add_systems(
+        (retry_button_clicked, main_menu_button_clicked).in_set(OnUpdate(GameState::GameOver)),
+      )
+      .add_systems(
+        (retry_button_clicked, main_menu_button_clicked).in_set(OnUpdate(GameState::Victory)),
+      )
+      .add_system(despawn_gameplay.in_schedule(OnExit(GameState::GameOver)))
+      .add_system(despawn_gameplay.in_schedule(OnExit(GameState::Victory)));
+  }
+}
+
+#[derive(Component)]
+pub struct ResultsUIRoot;
+
+#[derive(Component)]
+pub struct RetryButton;
+
+#[derive(Component)]
+pub struct MainMenuButton;
+
+fn retry_button_clicked(
+  mut commands: Commands,
+  interactions: Query<&Interaction, (With<RetryButton>, Changed<Interaction>)>,
+  results_root: Query<Entity, With<ResultsUIRoot>>,
+  mut game_state: ResMut<NextState<GameState>>,
+) {
+  for interaction in &interactions {
+    if matches!(interaction, Interaction::Clicked) {
+      let root_entity = results_root.single();
+      commands.entity(root_entity).despawn_recursive();
+
+      game_state.set(GameState::Gameplay);
+    }
+  }
+}
+
+fn main_menu_button_clicked(
+  mut commands: Commands,
+  interactions: Query<&Interaction, (With<MainMenuButton>, Changed<Interaction>)>,
+  results_root: Query<Entity, With<ResultsUIRoot>>,
+  mut game_state: ResMut<NextState<GameState>>,
+) {
+  for interaction in &interactions {
+    if matches!(interaction, Interaction::Clicked) {
+      let root_entity = results_root.single();
+      commands.entity(root_entity).despawn_recursive();
+
+      game_state.set(GameState::MainMenu);
+    }
+  }
+}
+
+fn spawn_results_screen(
+  mut commands: Commands,
+  assets: Res<GameAssets>,
+  game_state: Res<State<GameState>>,
+  game_data: Res<GameData>,
+  waves: Res<Assets<Waves>>,
+  player: Query<&Player>,
+  towers: Query<(&Tower, &TowerLifetimeStats, &TowerType)>,
+) {
+  let Some(waves) = waves.get(&game_data.enemy_waves)
+    else { return; };
+  let player = player.single();
+
+  let victory = game_state.0 == GameState::Victory;
+  let waves_survived = if victory {
+    waves.waves.len()
+  } else {
+    waves.current
+  };
+  // Endless runs have no last wave
+  let waves_total = if waves.endless {
+    "∞".to_string()
+  } else {
+    waves.waves.len().to_string()
+  };
+
+  let mut towers = towers.iter().collect::<Vec<_>>();
+  towers.sort_by_key(|(tower, ..)| std::cmp::Reverse(tower.total_damage));
+
+  let text_style = TextStyle {
+    font: assets.font.clone(),
+    font_size: 30.,
+    color: Color::WHITE,
+  };
+
+  commands
+    .spawn(NodeBundle {
+      background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.75)),
+      style: Style {
+        size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+        position_type: PositionType::Absolute,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        flex_direction: FlexDirection::Column,
+        ..default()
+      },
+      // Draw on top of the gameplay UI
+      z_index: ZIndex::Global(10),
+      ..default()
+    })
+    .insert(ResultsUIRoot)
+    .insert(Name::new("ResultsUI"))
+    .with_children(|commands| {
+      commands.spawn(
+        TextBundle::from_section(
+          if victory { "Victory!" } else { "Game Over" },
+          TextStyle {
+            font: assets.font.clone(),
+            font_size: 90.,
+            color: if victory { Color::GOLD } else { Color::CRIMSON },
+          },
+        )
+        .with_style(Style {
+          margin: UiRect::all(Val::Percent(2.)),
+          ..default()
+        }),
+      );
+
+      commands.spawn(TextBundle::from_section(
+        format!("Waves survived: {}/{}", waves_survived, waves_total),
+        text_style.clone(),
+      ));
+      commands.spawn(TextBundle::from_section(
+        format!("Money earned: ${}", player.money_earned),
+        text_style.clone(),
+      ));
+
+      // Damage dealt by each tower
+      for (tower, lifetime_stats, tower_type) in towers {
+        commands.spawn(TextBundle::from_section(
+          format!(
+            "{} Wizard: {} damage, {} kills, {:.0}% accuracy ({})",
+            tower_type,
+            tower.total_damage,
+            lifetime_stats.kills,
+            lifetime_stats.accuracy(),
+            format_damage_breakdown(lifetime_stats, 3),
+          ),
+          TextStyle {
+            font_size: 22.,
+            ..text_style.clone()
+          },
+        ));
+      }
+
+      // Retry and main menu buttons
+      commands
+        .spawn(NodeBundle {
+          style: Style {
+            margin: UiRect::top(Val::Percent(2.)),
+            ..default()
+          },
+          ..default()
+        })
+        .with_children(|commands| {
+          commands
+            .spawn(results_button())
+            .with_children(|commands| {
+              commands.spawn(TextBundle::from_section("Retry", text_style.clone()));
+            })
+            .insert(RetryButton)
+            .insert(Name::new("RetryButton"));
+
+          commands
+            .spawn(results_button())
+            .with_children(|commands| {
+              commands.spawn(TextBundle::from_section("Main Menu", text_style.clone()));
+            })
+            .insert(MainMenuButton)
+            .insert(Name::new("MainMenuButton"));
+        });
+    });
+}
+
+fn results_button() -> ButtonBundle {
+  ButtonBundle {
+    style: Style {
+      size: Size::new(Val::Px(220.), Val::Px(60.)),
+      justify_content: JustifyContent::Center,
+      align_items: AlignItems::Center,
+      margin: UiRect::all(Val::Px(15.)),
+      ..default()
+    },
+    background_color: BackgroundColor(Color::ORANGE_RED),
+    ..default()
+  }
+}
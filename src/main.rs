@@ -20,8 +20,24 @@ mod movement;
 pub use movement::*;
 mod game_data;
 pub use game_data::*;
+mod save;
+pub use save::*;
+mod simulation;
+pub use simulation::*;
+mod replay;
+pub use replay::*;
 
 fn main() {
+  // `--headless <scenario.ron>` plays a scenario without a window and prints the results as JSON
+  let args = std::env::args().collect::<Vec<_>>();
+  if let Some(index) = args.iter().position(|arg| arg == "--headless") {
+    match args.get(index + 1) {
+      Some(path) => run_headless_cli(path),
+      None => eprintln!("Usage: --headless <scenario.ron>"),
+    }
+    return;
+  }
+
   App::new()
     // Background of window. Set colour of screen on each refresh
     .insert_resource(ClearColor(Color::rgb(0.1, 0.1, 0.1)))
@@ -42,31 +58,41 @@ fn main() {
         }),
     )
     // Game State
-    .add_state::<GameState>()
+    .add_plugin(GameStatePlugin)
     // Asset loading
     .add_plugin(RonAssetPlugin::<EnemyTypeStats>::new(&["enemy_types.ron"]))
     .add_plugin(RonAssetPlugin::<Map>::new(&["map.ron"]))
     .add_plugin(RonAssetPlugin::<TowerTypeStats>::new(&["tower_stats.ron"]))
     .add_plugin(RonAssetPlugin::<Upgrades>::new(&["upgrades.ron"]))
     .add_plugin(RonAssetPlugin::<Waves>::new(&["waves.ron"]))
-    .add_loading_state(
-      LoadingState::new(GameState::AssetLoading).continue_to_state(GameState::MainMenu),
-    )
+    // `LoadingPlugin` moves on to the main menu once the loaded data has been checked
+    .add_loading_state(LoadingState::new(GameState::AssetLoading))
     .add_collection_to_loading_state::<_, GameData>(GameState::AssetLoading)
     // Plugins
+    .add_plugin(LoadingPlugin)
     .add_plugin(MainMenuPlugin)
+    .add_plugin(LevelSelectPlugin)
     .add_plugin(GameplayUIPlugin)
+    .add_plugin(ResultsPlugin)
+    .add_plugin(PauseMenuPlugin)
+    .add_plugin(GameSpeedPlugin)
+    .add_plugin(SavePlugin)
+    .add_plugin(ReplayPlugin)
+    .add_plugin(PlayerCommandPlugin)
     .add_plugin(MapPlugin)
+    .add_plugin(MapEditorPlugin)
     //.add_plugin(SpawnScenePlugin)
     .add_plugin(SettingsPlugin)
     .add_plugin(AssetPlugin)
     .add_plugin(PlayerPlugin)
     .add_plugin(BasePlugin)
     .add_plugin(TowerPlugin)
+    .add_plugin(TowerLifetimeStatsPlugin)
     .add_plugin(TowerButtonPlugin)
     .add_plugin(TowerSelectionPlugin)
     .add_plugin(TowerUIPlugin)
     .add_plugin(EnemyPlugin)
+    .add_plugin(EnemyAbilityPlugin)
     .add_plugin(WavePlugin)
     .add_plugin(BulletPlugin)
     .add_plugin(MovementPlugin)
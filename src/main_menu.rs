@@ -1,6 +1,12 @@
 mod game_state;
 pub use game_state::*;
 
+mod loading;
+pub use loading::*;
+
+mod level_select;
+pub use level_select::*;
+
 mod main_menu;
 pub use main_menu::*;
 
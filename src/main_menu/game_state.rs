@@ -1,9 +1,171 @@
+use bevy::ecs::event::Event;
+use bevy::ecs::schedule::ExecutorKind;
 use bevy::prelude::*;
+use bevy::transform::systems::{propagate_transforms, sync_simple_transforms};
+use std::time::Duration;
+
+use crate::{
+  reset_simulation_rng, Base, Enemy, GameplayUIRoot, Player, ReplayUI, SimulationRng,
+  SpriteFollower, TileMap, Tower, TowerUIRoot, TowerUpgradeUI,
+};
+
+// Game states and the gameplay system sets, shared by the game and headless simulations
+pub struct GameStatePlugin;
+
+impl Plugin for GameStatePlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .add_state::<GameState>()
+      .add_state::<GameplayState>()
+      .configure_set(
+        GameplaySet::Input
+          .in_set(OnUpdate(GameState::Gameplay))
+          .run_if(in_state(GameplayState::Running)),
+      )
+      .insert_resource(FixedTime::new(SIMULATION_TIMESTEP))
+      .init_resource::<SimulationRng>()
+      .init_resource::<SimulationTick>()
+      .add_systems(
+        (reset_simulation_rng, reset_simulation_tick).in_schedule(OnEnter(GameState::Gameplay)),
+      )
+      .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+        // Systems run one after another in the same order every step,
+        // so the outcome doesn't depend on how threads were scheduled
+        schedule
+          .set_executor_kind(ExecutorKind::SingleThreaded)
+          .configure_set(
+            SimulationStep::Commands
+              .before(SimulationStep::Prepare)
+              .run_if(in_state(GameState::Gameplay))
+              .run_if(in_state(GameplayState::Running)),
+          )
+          .configure_set(
+            SimulationStep::Prepare
+              .before(GameplaySet::Simulation)
+              .run_if(in_state(GameState::Gameplay)),
+          )
+          .configure_set(
+            GameplaySet::Simulation
+              .run_if(in_state(GameState::Gameplay))
+              .run_if(in_state(GameplayState::Running)),
+          )
+          .configure_set(SimulationStep::Events.after(GameplaySet::Simulation));
+      })
+      // Bullets, enemies and turned towers have moved since the last step
+      .add_systems(
+        (sync_simple_transforms, propagate_transforms)
+          .in_set(SimulationStep::Prepare)
+          .in_schedule(CoreSchedule::FixedUpdate),
+      )
+      .add_system(
+        advance_simulation_tick
+          .in_set(GameplaySet::Simulation)
+          .in_schedule(CoreSchedule::FixedUpdate),
+      );
+  }
+}
+
+// Length of every simulation step. Gameplay advances in whole steps whatever the frame rate,
+// faster game speeds run more of them per frame
+pub const SIMULATION_TIMESTEP: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+// Number of simulation steps since the level started
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq)]
+pub struct SimulationTick(pub u64);
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Default, States)]
 pub enum GameState {
   #[default]
   AssetLoading,
   MainMenu,
+  LevelSelect,
+  MapEditor,
   Gameplay,
+  GameOver,
+  Victory,
+}
+
+// State of a level that is being played
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default, States)]
+pub enum GameplayState {
+  #[default]
+  Running,
+  Paused,
+}
+
+// Systems that only run during gameplay and stop while the game is paused
+#[derive(SystemSet, Debug, Clone, Eq, PartialEq, Hash)]
+pub enum GameplaySet {
+  // Player input - placing, selecting, upgrading and selling towers
+  Input,
+  // Enemies, waves, towers and bullets. Runs in `CoreSchedule::FixedUpdate`,
+  // once per `SIMULATION_TIMESTEP`
+  Simulation,
+}
+
+// Parts of every fixed simulation step around `GameplaySet::Simulation`
+#[derive(SystemSet, Debug, Clone, Eq, PartialEq, Hash)]
+pub enum SimulationStep {
+  // Player commands sent since the last step are carried out
+  Commands,
+  // World positions and lookups the simulation reads are brought up to date
+  Prepare,
+  // Events sent before the previous step are dropped
+  Events,
+}
+
+pub trait SimulationEvents {
+  // Events sent or read by the simulation. They are kept until a whole step has seen them,
+  // rather than for two frames, so none are lost when a frame runs no steps or several
+  fn add_simulation_event<T: Event>(&mut self) -> &mut Self;
+}
+
+impl SimulationEvents for App {
+  fn add_simulation_event<T: Event>(&mut self) -> &mut Self {
+    if !self.world.contains_resource::<Events<T>>() {
+      self.init_resource::<Events<T>>().add_system(
+        Events::<T>::update_system
+          .in_set(SimulationStep::Events)
+          .in_schedule(CoreSchedule::FixedUpdate),
+      );
+    }
+    self
+  }
+}
+
+fn reset_simulation_tick(mut tick: ResMut<SimulationTick>) {
+  *tick = SimulationTick::default();
+}
+
+fn advance_simulation_tick(mut tick: ResMut<SimulationTick>) {
+  tick.0 += 1;
+}
+
+// Remove everything spawned for a level, so it can be started again from scratch
+#[allow(clippy::type_complexity)]
+pub fn despawn_gameplay(
+  mut commands: Commands,
+  entities: Query<
+    Entity,
+    (
+      Or<(
+        With<TileMap>,
+        With<Tower>,
+        With<Enemy>,
+        With<Player>,
+        With<Base>,
+        With<GameplayUIRoot>,
+        With<TowerUIRoot>,
+        With<TowerUpgradeUI>,
+        With<SpriteFollower>,
+        With<ReplayUI>,
+      )>,
+      // Children are despawned together with their parents
+      Without<Parent>,
+    ),
+  >,
+) {
+  for entity in &entities {
+    commands.entity(entity).despawn_recursive();
+  }
 }
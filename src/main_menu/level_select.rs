@@ -0,0 +1,346 @@
+use bevy::prelude::*;
+
+use crate::assets::*;
+use crate::main_menu::*;
+use crate::{GameData, Map, MapError};
+
+pub struct LevelSelectPlugin;
+
+impl Plugin for LevelSelectPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_resource::<EndlessMode>()
+      .add_system(load_levels.in_schedule(OnExit(GameState::AssetLoading)))
+      .add_system(spawn_level_select.in_schedule(OnEnter(GameState::LevelSelect)))
+      .add_systems(
+        (level_button_clicked, back_button_clicked, endless_button_clicked)
+          .in_set(OnUpdate(GameState::LevelSelect)),
+      );
+  }
+}
+
+pub struct Level {
+  pub name: String,
+  pub map: Handle<Map>,
+  pub thumbnail: Handle<Image>,
+  // Problems that keep the map from being played, listed on its button instead
+  pub errors: Vec<MapError>,
+}
+
+impl Level {
+  pub fn is_playable(&self) -> bool {
+    self.errors.is_empty()
+  }
+}
+
+// Every map found in the data folder, sorted by file name
+#[derive(Resource, Default)]
+pub struct Levels {
+  pub levels: Vec<Level>,
+}
+
+// Map that is loaded when entering gameplay
+#[derive(Resource)]
+pub struct SelectedLevel {
+  pub name: String,
+  pub map: Handle<Map>,
+  // Keep generating waves after the scripted ones instead of winning
+  pub endless: bool,
+}
+
+// Whether levels picked in the level select are played in endless mode
+#[derive(Resource, Default)]
+pub struct EndlessMode(pub bool);
+
+#[derive(Component)]
+pub struct LevelSelectUIRoot;
+
+#[derive(Component)]
+pub struct LevelButton {
+  pub index: usize,
+}
+
+#[derive(Component)]
+pub struct BackButton;
+
+#[derive(Component)]
+pub struct EndlessButton;
+
+#[derive(Component)]
+pub struct EndlessText;
+
+// Strip the extension of a map file - "level1.map.ron" -> "level1"
+pub fn level_name(file_name: &str) -> Option<&str> {
+  file_name.strip_suffix(".map.ron")
+}
+
+fn load_levels(
+  mut commands: Commands,
+  game_data: Res<GameData>,
+  asset_server: Res<AssetServer>,
+  maps: Res<Assets<Map>>,
+  mut images: ResMut<Assets<Image>>,
+) {
+  let mut levels = vec![];
+
+  for map in &game_data.levels {
+    let Some(asset_path) = asset_server.get_handle_path(map)
+      else { continue; };
+    // The data folder also holds the stats and wave files
+    let Some(name) = asset_path
+      .path()
+      .file_name()
+      .and_then(|file_name| file_name.to_str())
+      .and_then(level_name)
+      else { continue; };
+
+    let Some(map_asset) = maps.get(map)
+      else { continue; };
+
+    let errors = map_asset.validate().err().unwrap_or_default();
+    for error in &errors {
+      warn!("Level {} can't be played: {}", name, error);
+    }
+
+    levels.push(Level {
+      name: name.to_string(),
+      thumbnail: images.add(map_asset.thumbnail()),
+      map: map.clone(),
+      errors,
+    });
+  }
+
+  levels.sort_by(|a, b| a.name.cmp(&b.name));
+  commands.insert_resource(Levels { levels });
+}
+
+fn level_button_clicked(
+  mut commands: Commands,
+  interactions: Query<(&Interaction, &LevelButton), Changed<Interaction>>,
+  menu_root: Query<Entity, With<LevelSelectUIRoot>>,
+  levels: Res<Levels>,
+  endless_mode: Res<EndlessMode>,
+  mut game_state: ResMut<NextState<GameState>>,
+) {
+  for (interaction, level_button) in &interactions {
+    let level = &levels.levels[level_button.index];
+    if matches!(interaction, Interaction::Clicked) && level.is_playable() {
+      let root_entity = menu_root.single();
+      commands.entity(root_entity).despawn_recursive();
+
+      commands.insert_resource(SelectedLevel {
+        name: level.name.clone(),
+        map: level.map.clone(),
+        endless: endless_mode.0,
+      });
+      game_state.set(GameState::Gameplay);
+    }
+  }
+}
+
+fn back_button_clicked(
+  mut commands: Commands,
+  interactions: Query<&Interaction, (With<BackButton>, Changed<Interaction>)>,
+  menu_root: Query<Entity, With<LevelSelectUIRoot>>,
+  mut game_state: ResMut<NextState<GameState>>,
+) {
+  for interaction in &interactions {
+    if matches!(interaction, Interaction::Clicked) {
+      let root_entity = menu_root.single();
+      commands.entity(root_entity).despawn_recursive();
+
+      game_state.set(GameState::MainMenu);
+    }
+  }
+}
+
+fn endless_button_clicked(
+  interactions: Query<&Interaction, (With<EndlessButton>, Changed<Interaction>)>,
+  mut endless_mode: ResMut<EndlessMode>,
+  mut endless_text: Query<&mut Text, With<EndlessText>>,
+) {
+  for interaction in &interactions {
+    if matches!(interaction, Interaction::Clicked) {
+      endless_mode.0 = !endless_mode.0;
+      for mut text in &mut endless_text {
+        text.sections[0].value = endless_label(endless_mode.0);
+      }
+    }
+  }
+}
+
+fn endless_label(endless: bool) -> String {
+  format!("Endless: {}", if endless { "On" } else { "Off" })
+}
+
+fn spawn_level_select(
+  mut commands: Commands,
+  assets: Res<GameAssets>,
+  levels: Res<Levels>,
+  endless_mode: Res<EndlessMode>,
+) {
+  commands
+    .spawn(NodeBundle {
+      style: Style {
+        size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        flex_direction: FlexDirection::Column,
+        ..default()
+      },
+      ..default()
+    })
+    .insert(LevelSelectUIRoot)
+    .insert(Name::new("LevelSelectUI"))
+    .with_children(|commands| {
+      commands.spawn(TextBundle {
+        style: Style {
+          margin: UiRect::all(Val::Percent(2.)),
+          ..default()
+        },
+        text: Text::from_section(
+          "Select Level",
+          TextStyle {
+            font: assets.font.clone(),
+            font_size: 70.,
+            color: Color::CYAN,
+          },
+        ),
+        ..default()
+      });
+
+      // Level buttons with a preview of each map
+      commands
+        .spawn(NodeBundle {
+          style: Style {
+            justify_content: JustifyContent::Center,
+            flex_wrap: FlexWrap::Wrap,
+            ..default()
+          },
+          ..default()
+        })
+        .with_children(|commands| {
+          for (index, level) in levels.levels.iter().enumerate() {
+            commands
+              .spawn(ButtonBundle {
+                style: Style {
+                  flex_direction: FlexDirection::Column,
+                  align_items: AlignItems::Center,
+                  padding: UiRect::all(Val::Px(10.)),
+                  margin: UiRect::all(Val::Px(15.)),
+                  ..default()
+                },
+                background_color: BackgroundColor(if level.is_playable() {
+                  Color::GOLD
+                } else {
+                  Color::GRAY
+                }),
+                ..default()
+              })
+              .with_children(|commands| {
+                commands.spawn(ImageBundle {
+                  style: Style {
+                    size: Size::new(Val::Px(256.), Val::Px(144.)),
+                    ..default()
+                  },
+                  image: level.thumbnail.clone().into(),
+                  ..default()
+                });
+
+                commands.spawn(TextBundle {
+                  style: Style {
+                    margin: UiRect::top(Val::Px(10.)),
+                    ..default()
+                  },
+                  text: Text::from_section(
+                    &level.name,
+                    TextStyle {
+                      font: assets.font.clone(),
+                      font_size: 30.,
+                      color: Color::WHITE,
+                    },
+                  ),
+                  ..default()
+                });
+
+                for error in &level.errors {
+                  commands.spawn(TextBundle {
+                    style: Style {
+                      max_size: Size::new(Val::Px(256.), Val::Undefined),
+                      ..default()
+                    },
+                    text: Text::from_section(
+                      error.to_string(),
+                      TextStyle {
+                        font: assets.font.clone(),
+                        font_size: 18.,
+                        color: Color::CRIMSON,
+                      },
+                    ),
+                    ..default()
+                  });
+                }
+              })
+              .insert(LevelButton { index })
+              .insert(Name::new(format!("LevelButton {}", level.name)));
+          }
+        });
+
+      // Endless mode toggle, applies to the next level picked
+      commands
+        .spawn(ButtonBundle {
+          style: Style {
+            size: Size::new(Val::Px(260.), Val::Px(60.)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+          },
+          background_color: BackgroundColor(Color::PURPLE),
+          ..default()
+        })
+        .with_children(|commands| {
+          commands
+            .spawn(TextBundle::from_section(
+              endless_label(endless_mode.0),
+              TextStyle {
+                font: assets.font.clone(),
+                font_size: 36.,
+                color: Color::WHITE,
+              },
+            ))
+            .insert(EndlessText);
+        })
+        .insert(EndlessButton)
+        .insert(Name::new("EndlessButton"));
+
+      // Back to main menu
+      commands
+        .spawn(ButtonBundle {
+          style: Style {
+            size: Size::new(Val::Px(200.), Val::Px(60.)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            margin: UiRect::all(Val::Percent(2.)),
+            ..default()
+          },
+          background_color: BackgroundColor(Color::ORANGE_RED),
+          ..default()
+        })
+        .with_children(|commands| {
+          commands.spawn(TextBundle::from_section(
+            "Back",
+            TextStyle {
+              font: assets.font.clone(),
+              font_size: 36.,
+              color: Color::WHITE,
+            },
+          ));
+        })
+        .insert(BackButton)
+        .insert(Name::new("BackButton"));
+    });
+}
+
+#[cfg(test)]
+#[path = "main_menu/level_select_tests.rs"]
+mod tests;
@@ -0,0 +1,129 @@
+use bevy::prelude::*;
+
+use crate::{EnemyTypeStats, GameAssets, GameData, GameState, Waves};
+
+// Checks the loaded game data before the main menu is shown
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+  fn build(&self, app: &mut App) {
+    app.add_system(
+      check_game_data
+        .in_set(OnUpdate(GameState::AssetLoading))
+        .run_if(resource_exists::<GameData>()),
+    );
+  }
+}
+
+#[derive(Component)]
+pub struct LoadingErrorUIRoot;
+
+// Problems of every data file, as "file: problem" lines
+pub fn game_data_errors(
+  game_data: &GameData,
+  asset_server: &AssetServer,
+  waves: &Assets<Waves>,
+  enemy_stats: &Assets<EnemyTypeStats>,
+) -> Vec<String> {
+  let path = |handle: HandleUntyped| {
+    asset_server
+      .get_handle_path(handle)
+      .map_or("unknown file".to_string(), |path| {
+        path.path().display().to_string()
+      })
+  };
+  let waves_path = path(game_data.enemy_waves.clone_untyped());
+
+  let (Some(waves), Some(enemy_stats)) = (
+    waves.get(&game_data.enemy_waves),
+    enemy_stats.get(&game_data.enemy_type_stats),
+  ) else {
+    return vec![format!("{}: not loaded", waves_path)];
+  };
+
+  match waves.validate(enemy_stats) {
+    Ok(()) => vec![],
+    Err(errors) => errors
+      .iter()
+      .map(|error| format!("{}: {}", waves_path, error))
+      .collect(),
+  }
+}
+
+// Broken data would crash the game once a level starts, so the loading screen lists the
+// problems and stays up until the files are fixed
+#[allow(clippy::too_many_arguments)]
+fn check_game_data(
+  mut commands: Commands,
+  assets: Res<GameAssets>,
+  game_data: Res<GameData>,
+  asset_server: Res<AssetServer>,
+  waves: Res<Assets<Waves>>,
+  enemy_stats: Res<Assets<EnemyTypeStats>>,
+  error_screens: Query<(), With<LoadingErrorUIRoot>>,
+  mut game_state: ResMut<NextState<GameState>>,
+) {
+  if !error_screens.is_empty() {
+    return;
+  }
+
+  let errors = game_data_errors(&game_data, &asset_server, &waves, &enemy_stats);
+  if errors.is_empty() {
+    game_state.set(GameState::MainMenu);
+    return;
+  }
+
+  for error in &errors {
+    error!("{}", error);
+  }
+  spawn_loading_errors(&mut commands, &assets, &errors);
+}
+
+fn spawn_loading_errors(commands: &mut Commands, assets: &GameAssets, errors: &[String]) {
+  let text_style = |font_size: f32, color: Color| TextStyle {
+    font: assets.font.clone(),
+    font_size,
+    color,
+  };
+
+  commands
+    .spawn(NodeBundle {
+      style: Style {
+        size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        flex_direction: FlexDirection::Column,
+        ..default()
+      },
+      ..default()
+    })
+    .insert(LoadingErrorUIRoot)
+    .insert(Name::new("LoadingErrorUI"))
+    .with_children(|commands| {
+      commands.spawn(
+        TextBundle::from_section("Failed to load game data", text_style(60., Color::CRIMSON))
+          .with_style(Style {
+            margin: UiRect::all(Val::Percent(2.)),
+            ..default()
+          }),
+      );
+
+      for error in errors {
+        commands.spawn(TextBundle::from_section(
+          error.as_str(),
+          text_style(24., Color::WHITE),
+        ));
+      }
+
+      commands.spawn(
+        TextBundle::from_section(
+          "Fix the files in assets/data and restart the game",
+          text_style(24., Color::GRAY),
+        )
+        .with_style(Style {
+          margin: UiRect::all(Val::Percent(2.)),
+          ..default()
+        }),
+      );
+    });
+}
@@ -2,6 +2,7 @@ use bevy::{app::AppExit, prelude::*};
 
 use crate::assets::*;
 use crate::main_menu::*;
+use crate::{Replay, ReplayPlayback, ReplayState, SaveGame};
 
 pub struct MainMenuPlugin;
 
@@ -10,7 +11,14 @@ impl Plugin for MainMenuPlugin {
     app
       .add_system(spawn_main_menu.in_schedule(OnEnter(GameState::MainMenu)))
       .add_systems(
-        (start_button_clicked, exit_button_clicked).in_set(OnUpdate(GameState::MainMenu)),
+        (
+          start_button_clicked,
+          continue_button_clicked,
+          replay_button_clicked,
+          map_editor_button_clicked,
+          exit_button_clicked,
+        )
+          .in_set(OnUpdate(GameState::MainMenu)),
       );
   }
 }
@@ -21,6 +29,15 @@ pub struct MenuUIRoot;
 #[derive(Component)]
 pub struct StartButton;
 
+#[derive(Component)]
+pub struct ContinueButton;
+
+#[derive(Component)]
+pub struct ReplayButton;
+
+#[derive(Component)]
+pub struct MapEditorButton;
+
 #[derive(Component)]
 pub struct ExitButton;
 
@@ -35,11 +52,111 @@ fn start_button_clicked(
       let root_entity = menu_root.single();
       commands.entity(root_entity).despawn_recursive();
 
+      game_state.set(GameState::LevelSelect);
+    }
+  }
+}
+
+// Load the saved run and start its level
+fn continue_button_clicked(
+  mut commands: Commands,
+  interactions: Query<&Interaction, (With<ContinueButton>, Changed<Interaction>)>,
+  menu_root: Query<Entity, With<MenuUIRoot>>,
+  levels: Res<Levels>,
+  mut game_state: ResMut<NextState<GameState>>,
+) {
+  for interaction in &interactions {
+    if matches!(interaction, Interaction::Clicked) {
+      let save_game = match SaveGame::load() {
+        Ok(save_game) => save_game,
+        Err(error) => {
+          error!("Failed to load save: {}", error);
+          continue;
+        }
+      };
+      let Some(level) = levels.levels.iter().find(|level| level.name == save_game.level)
+        else {
+          error!("Level {} of the save no longer exists", save_game.level);
+          continue;
+        };
+      if !level.is_playable() {
+        error!("Level {} of the save can't be played", level.name);
+        continue;
+      }
+
+      let root_entity = menu_root.single();
+      commands.entity(root_entity).despawn_recursive();
+
+      commands.insert_resource(SelectedLevel {
+        name: level.name.clone(),
+        map: level.map.clone(),
+        endless: save_game.endless,
+      });
+      commands.insert_resource(save_game);
       game_state.set(GameState::Gameplay);
     }
   }
 }
 
+// Watch the last finished run again
+fn replay_button_clicked(
+  mut commands: Commands,
+  interactions: Query<&Interaction, (With<ReplayButton>, Changed<Interaction>)>,
+  menu_root: Query<Entity, With<MenuUIRoot>>,
+  levels: Res<Levels>,
+  mut game_state: ResMut<NextState<GameState>>,
+  mut replay_state: ResMut<NextState<ReplayState>>,
+) {
+  for interaction in &interactions {
+    if matches!(interaction, Interaction::Clicked) {
+      let replay = match Replay::load() {
+        Ok(replay) => replay,
+        Err(error) => {
+          error!("Failed to load replay: {}", error);
+          continue;
+        }
+      };
+      let Some(level) = levels.levels.iter().find(|level| level.name == replay.level)
+        else {
+          error!("Level {} of the replay no longer exists", replay.level);
+          continue;
+        };
+      if !level.is_playable() {
+        error!("Level {} of the replay can't be played", level.name);
+        continue;
+      }
+
+      let root_entity = menu_root.single();
+      commands.entity(root_entity).despawn_recursive();
+
+      commands.insert_resource(SelectedLevel {
+        name: level.name.clone(),
+        map: level.map.clone(),
+        endless: replay.endless,
+      });
+      commands.insert_resource(ReplayPlayback::new(replay));
+      replay_state.set(ReplayState::Playing);
+      game_state.set(GameState::Gameplay);
+    }
+  }
+}
+
+fn map_editor_button_clicked(
+  mut commands: Commands,
+  interactions: Query<&Interaction, (With<MapEditorButton>, Changed<Interaction>)>,
+  menu_root: Query<Entity, With<MenuUIRoot>>,
+  mut game_state: ResMut<NextState<GameState>>,
+) {
+  for interaction in &interactions {
+    if matches!(interaction, Interaction::Clicked) {
+      let root_entity = menu_root.single();
+      commands.entity(root_entity).despawn_recursive();
+
+      game_state.set(GameState::MapEditor);
+    }
+  }
+}
+
 fn exit_button_clicked(
   interactions: Query<&Interaction, (With<ExitButton>, Changed<Interaction>)>,
   mut exit: EventWriter<AppExit>,
@@ -61,6 +178,13 @@ fn spawn_main_menu(mut commands: Commands, assets: Res<GameAssets>) {
     .id();
   commands.entity(start_button).insert(StartButton);
 
+  // Only offer to continue when there is a run to continue
+  let continue_button = SaveGame::exists()
+    .then(|| spawn_text_button(&mut commands, &assets, "Continue", ContinueButton));
+  let replay_button = Replay::exists()
+    .then(|| spawn_text_button(&mut commands, &assets, "Watch Replay", ReplayButton));
+  let map_editor_button = spawn_text_button(&mut commands, &assets, "Map Editor", MapEditorButton);
+
   let exit_button = commands
     .spawn(ButtonBundle {
       style: spawn_button_style(),
@@ -70,7 +194,7 @@ fn spawn_main_menu(mut commands: Commands, assets: Res<GameAssets>) {
     .id();
   commands.entity(exit_button).insert(ExitButton);
 
-  commands
+  let menu_root = commands
     .spawn(NodeBundle {
       style: Style {
         size: Size::new(Val::Percent(100.), Val::Percent(100.)),
@@ -100,9 +224,47 @@ fn spawn_main_menu(mut commands: Commands, assets: Res<GameAssets>) {
       });
     })
     .add_child(start_button)
+    .id();
+
+  for button in [continue_button, replay_button].into_iter().flatten() {
+    commands.entity(menu_root).add_child(button);
+  }
+  commands
+    .entity(menu_root)
+    .add_child(map_editor_button)
     .add_child(exit_button);
 }
 
+fn spawn_text_button(
+  commands: &mut Commands,
+  assets: &GameAssets,
+  text: &str,
+  marker: impl Component,
+) -> Entity {
+  commands
+    .spawn(ButtonBundle {
+      style: Style {
+        size: Size::new(Val::Px(570.), Val::Px(100.)),
+        align_items: AlignItems::Center,
+        ..spawn_button_style()
+      },
+      background_color: BackgroundColor(Color::ORANGE_RED),
+      ..default()
+    })
+    .with_children(|commands| {
+      commands.spawn(TextBundle::from_section(
+        text,
+        TextStyle {
+          font: assets.font.clone(),
+          font_size: 60.,
+          color: Color::WHITE,
+        },
+      ));
+    })
+    .insert(marker)
+    .id()
+}
+
 fn spawn_button_style() -> Style {
   Style {
     size: Size::new(Val::Px(570.), Val::Px(147.)),
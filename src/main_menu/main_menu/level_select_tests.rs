@@ -0,0 +1,14 @@
+use super::*;
+
+#[test]
+fn test_level_name_strips_extension() {
+    assert_eq!(level_name("level1.map.ron"), Some("level1"));
+    assert_eq!(level_name("desert_canyon.map.ron"), Some("desert_canyon"));
+}
+
+#[test]
+fn test_level_name_ignores_other_data_files() {
+    assert_eq!(level_name("enemy.waves.ron"), None);
+    assert_eq!(level_name("stats.tower_stats.ron"), None);
+    assert_eq!(level_name("tower.upgrades.ron"), None);
+}
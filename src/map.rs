@@ -1,2 +1,6 @@
 mod map;
 pub use map::*;
+mod map_editor;
+pub use map_editor::*;
+mod validation;
+pub use validation::*;
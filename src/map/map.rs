@@ -1,22 +1,30 @@
 use bevy::prelude::*;
 use bevy::reflect::TypeUuid;
 use bevy::render::camera::ScalingMode;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
 use serde::{Deserialize, Serialize};
 
 use crate::gameplay_ui::*;
 use crate::movement::*;
-use crate::{Enemy, GameAssets, GameData, GameState, Path};
+use crate::{Enemy, GameAssets, GameState, GameplaySet, Path, SelectedLevel};
 
 pub struct MapPlugin;
 
 impl Plugin for MapPlugin {
   fn build(&self, app: &mut App) {
     app
+      .add_system(setup_camera.in_schedule(OnExit(GameState::AssetLoading)))
+      // The selected map has to be inserted as a resource before it can be rendered
       .add_systems(
-        (load_map, setup_camera.after(load_map)).in_schedule(OnExit(GameState::AssetLoading)),
+        (load_map, apply_system_buffers, render_map)
+          .chain()
+          .in_schedule(OnEnter(GameState::Gameplay)),
       )
-      .add_system(render_map.in_schedule(OnEnter(GameState::Gameplay)))
-      .add_systems((update_enemy_checkpoint, despawn_enemy).in_set(OnUpdate(GameState::Gameplay)));
+      .add_systems(
+        (update_enemy_checkpoint, despawn_enemy)
+          .in_set(GameplaySet::Simulation)
+          .in_schedule(CoreSchedule::FixedUpdate),
+      );
   }
 }
 
@@ -68,68 +76,199 @@ impl Coordinate {
   }
 }
 
-#[derive(Resource, Serialize, Deserialize, TypeUuid, Default)]
+#[derive(Resource, Serialize, Deserialize, TypeUuid, Default, Clone)]
 #[uuid = "58d181c2-39f7-4ac7-8ae7-b3cee0667ce2"]
 pub struct Map {
   pub width: usize,
   pub height: usize,
   pub tiles: Vec<Vec<Tile>>,
   pub tile_size: usize,
-  pub checkpoints: Vec<Vec3>,
+  // Checkpoints of every path, indexed by the path ids of `Tile::Path`
+  pub checkpoints: Vec<Vec<Vec3>>,
 }
 
-fn load_map(game_data: Res<GameData>, mut map: ResMut<Assets<Map>>) {
-  let Some(map) = map.get_mut(&game_data.map)
-  else { return; };
+// Copy the selected map asset into the Map resource used during gameplay
+fn load_map(
+  mut commands: Commands,
+  selected_level: Res<SelectedLevel>,
+  maps: Res<Assets<Map>>,
+  mut camera: Query<&mut Transform, With<MainCamera>>,
+) {
+  let Some(map) = maps.get(&selected_level.map)
+    else { return; };
+
+  let mut map = map.clone();
+  map.prepare();
 
-  let mut path_tiles = vec![];
-  let mut spawn: Point = Default::default();
-  let mut end: Point = Default::default();
+  // Center the camera on the map. Headless simulations don't have one
+  if let Ok(mut camera_transform) = camera.get_single_mut() {
+    camera_transform.translation.x = (map.width as f32 / 2. - 0.5) * map.tile_size as f32;
+    camera_transform.translation.y = (map.height as f32 / 2. - 0.5) * map.tile_size as f32;
+  }
 
-  map.tiles.reverse();
+  commands.insert_resource(map);
+}
 
-  for (y, row) in map.tiles.iter().enumerate() {
-    for (x, tile) in row.iter().enumerate() {
-      match tile {
-        Tile::Spawn => spawn = Point { x, y },
-        Tile::Path(_) => path_tiles.push(Point { x, y }),
-        Tile::End => end = Point { x, y },
-        _ => {}
+impl Map {
+  // Flip the rows so the first row is at the bottom of the world and generate the enemy paths
+  pub fn prepare(&mut self) {
+    let mut path_tiles: Vec<Vec<Point>> = vec![];
+    let mut spawns = vec![];
+    let mut ends = vec![];
+
+    self.tiles.reverse();
+
+    for (y, row) in self.tiles.iter().enumerate() {
+      for (x, tile) in row.iter().enumerate() {
+        match tile {
+          Tile::Spawn => spawns.push(Point { x, y }),
+          // Tiles shared by several paths are where paths fork or merge
+          Tile::Path(ids) => {
+            for id in ids {
+              if path_tiles.len() <= *id {
+                path_tiles.resize(*id + 1, vec![]);
+              }
+              path_tiles[*id].push(Point { x, y });
+            }
+          }
+          Tile::End => ends.push(Point { x, y }),
+          _ => {}
+        }
       }
     }
+
+    self.checkpoints = path_tiles
+      .into_iter()
+      .map(|tiles| {
+        // Each path starts at the spawn and finishes at the end that its tiles touch
+        let touching = |point: &&Point| tiles.iter().any(|tile| point.is_adjacent_to(*tile));
+        let spawn = spawns.iter().find(touching).copied().unwrap_or_default();
+        let end = ends.iter().find(touching).copied().unwrap_or_default();
+
+        self.create_checkpoints(tiles, spawn, end)
+      })
+      .collect();
   }
 
-  map.checkpoints.clear();
-  map.create_checkpoints(path_tiles, spawn, end);
-}
+  // Tile under a world position. Tiles are centered on their coordinate
+  pub fn tile_at(&self, position: Vec3) -> Option<&Tile> {
+    let point = self.tile_coordinate(position)?;
+    Some(&self.tiles[point.y][point.x])
+  }
 
-impl Map {
-  fn create_checkpoints(&mut self, mut path_tiles: Vec<Point>, spawn: Point, end: Point) {
+  // Column and row of the tile under a world position, if there is one
+  pub fn tile_coordinate(&self, position: Vec3) -> Option<Point> {
+    let tile_size = self.tile_size as f32;
+    let column = ((position.x + tile_size / 2.) / tile_size).floor();
+    let row = ((position.y + tile_size / 2.) / tile_size).floor();
+    if column < 0. || row < 0. {
+      return None;
+    }
+
+    let point = Point {
+      x: column as usize,
+      y: row as usize,
+    };
+    self.tiles.get(point.y)?.get(point.x)?;
+    Some(point)
+  }
+
+  pub fn path_count(&self) -> usize {
+    self.checkpoints.len()
+  }
+
+  // Small preview of the map with one pixel per tile, used in the level selection screen
+  pub fn thumbnail(&self) -> Image {
+    let mut data = Vec::with_capacity(self.width * self.height * 4);
+    for y in 0..self.height {
+      for x in 0..self.width {
+        let tile = self.tiles.get(y).and_then(|row| row.get(x)).unwrap_or(&Tile::Empty);
+        data.extend_from_slice(&tile.thumbnail_color());
+      }
+    }
+
+    Image::new(
+      Extent3d {
+        width: self.width as u32,
+        height: self.height as u32,
+        depth_or_array_layers: 1,
+      },
+      TextureDimension::D2,
+      data,
+      TextureFormat::Rgba8UnormSrgb,
+    )
+  }
+
+  fn create_checkpoints(&self, path_tiles: Vec<Point>, spawn: Point, end: Point) -> Vec<Vec3> {
+    let mut checkpoints = vec![];
     let offset_distance = (self.tile_size * 2) as f32;
     let mut spawn_coord = spawn.to_coordinate(self.tile_size, false);
 
-    // Spawn location
-    if spawn.y == 0 { // Bottom
-      spawn_coord.y -= offset_distance;
-    } else if spawn.y == self.height - 1 { // Top
-      spawn_coord.y += offset_distance;
-    } else if spawn.x == 0 { // Left
-      spawn_coord.x -= offset_distance;
-    } else if spawn.x == self.width - 1 { // Right
-      spawn_coord.x += offset_distance;
+    // Spawn location - only spawns on the edge of the map are moved off screen
+    if spawn.x < self.width && spawn.y < self.height {
+      if spawn.y == 0 { // Bottom
+        spawn_coord.y -= offset_distance;
+      } else if spawn.y == self.height - 1 { // Top
+        spawn_coord.y += offset_distance;
+      } else if spawn.x == 0 { // Left
+        spawn_coord.x -= offset_distance;
+      } else if spawn.x == self.width - 1 { // Right
+        spawn_coord.x += offset_distance;
+      }
     }
 
-    self.checkpoints.push(spawn_coord.to_vec3());
+    checkpoints.push(spawn_coord.to_vec3());
 
-    let mut last_point = spawn;
-
-    while let Some(next_idx) = path_tiles.iter().position(|p| last_point.is_adjacent_to(*p)) {
-      let next_point = path_tiles.remove(next_idx);
-      self.checkpoints.push(next_point.to_coordinate(self.tile_size, true).to_vec3());
-      last_point = next_point;
+    let (walked, _) = walk_path(spawn, &path_tiles);
+    for point in walked {
+      checkpoints.push(point.to_coordinate(self.tile_size, true).to_vec3());
     }
 
-    self.checkpoints.push(end.to_coordinate(self.tile_size, true).to_vec3());
+    checkpoints.push(end.to_coordinate(self.tile_size, true).to_vec3());
+    checkpoints
+  }
+}
+
+// Tiles of a path in the order enemies walk them, starting next to the spawn. A tile with path
+// tiles on all four sides is where the path crosses itself, it is walked twice, straight through.
+// The walk stops where there is no next tile, or at the tile the path branches from if there are
+// several, which is returned too
+pub fn walk_path(spawn: Point, tiles: &[Point]) -> (Vec<Point>, Option<Point>) {
+  let is_crossing =
+    |point: Point| tiles.iter().filter(|tile| point.is_adjacent_to(**tile)).count() == 4;
+  let mut visits_left = tiles
+    .iter()
+    .map(|tile| if is_crossing(*tile) { 2 } else { 1 })
+    .collect::<Vec<_>>();
+
+  let mut walked = vec![];
+  let mut previous: Option<Point> = None;
+  let mut current = spawn;
+  loop {
+    let straight_ahead = previous.and_then(|previous| {
+      Some(Point {
+        x: (current.x * 2).checked_sub(previous.x)?,
+        y: (current.y * 2).checked_sub(previous.y)?,
+      })
+    });
+    let crossing = previous.is_some() && is_crossing(current);
+
+    let next = (0..tiles.len())
+      .filter(|index| visits_left[*index] > 0)
+      .filter(|index| current.is_adjacent_to(tiles[*index]) && Some(tiles[*index]) != previous)
+      .filter(|index| !crossing || Some(tiles[*index]) == straight_ahead)
+      .collect::<Vec<_>>();
+
+    match next.as_slice() {
+      [] => return (walked, None),
+      [index] => {
+        visits_left[*index] -= 1;
+        previous = Some(current);
+        current = tiles[*index];
+        walked.push(current);
+      }
+      _ => return (walked, Some(current)),
+    }
   }
 }
 
@@ -137,12 +276,8 @@ impl Map {
 #[derive(Component)]
 pub struct MainCamera;
 
-fn setup_camera(mut commands: Commands, game_data: Res<GameData>, map: Res<Assets<Map>>) {
-  let Some(map) = map.get(&game_data.map)
-    else { return; };
+fn setup_camera(mut commands: Commands) {
   let mut camera = Camera2dBundle::default();
-  camera.transform.translation.x = (map.width as f32 / 2. - 0.5) * map.tile_size as f32;
-  camera.transform.translation.y = (map.height as f32 / 2. - 0.5) * map.tile_size as f32;
   camera.projection.scaling_mode = ScalingMode::AutoMin {
     min_width: 1280.,
     min_height: 720.0,
@@ -150,15 +285,7 @@ fn setup_camera(mut commands: Commands, game_data: Res<GameData>, map: Res<Asset
   commands.spawn((camera, MainCamera));
 }
 
-fn render_map(
-  mut commands: Commands,
-  game_data: Res<GameData>,
-  map: Res<Assets<Map>>,
-  assets: Res<GameAssets>,
-) {
-  let Some(map) = map.get(&game_data.map)
-    else { return; };
-
+pub fn render_map(mut commands: Commands, map: Res<Map>, assets: Res<GameAssets>) {
   commands
     .spawn(SpatialBundle::default())
     .with_children(|commands| {
@@ -197,6 +324,19 @@ pub enum Tile {
   Empty,
 }
 
+impl Tile {
+  pub fn thumbnail_color(&self) -> [u8; 4] {
+    match self {
+      Tile::Grass => [86, 158, 58, 255],
+      Tile::Water => [52, 118, 196, 255],
+      Tile::Path(_) => [196, 160, 104, 255],
+      Tile::Spawn => [214, 64, 64, 255],
+      Tile::End => [232, 200, 56, 255],
+      Tile::Empty => [0, 0, 0, 0],
+    }
+  }
+}
+
 #[derive(Component)]
 pub struct MapTile {
   pub coordinate: Point,
@@ -207,16 +347,12 @@ fn despawn_enemy(
   mut commands: Commands,
   mut enemies: Query<(Entity, &Enemy, &mut Path)>,
   mut base: Query<&mut Base>,
-  game_data: Res<GameData>,
-  map: Res<Assets<Map>>,
+  map: Res<Map>,
 ) {
-  let Some(map) = map.get(&game_data.map)
-    else { return; };
-
   let mut base = base.single_mut();
 
   for (entity, enemy, path) in &mut enemies {
-    if path.index >= map.checkpoints.len() {
+    if path.index >= map.checkpoints[path.id].len() {
       damage_base(&mut commands, &entity, enemy.health, &mut base);
     }
   }
@@ -224,33 +360,31 @@ fn despawn_enemy(
 
 fn update_enemy_checkpoint(
   mut enemies: Query<(&mut Movement, &mut Transform, &mut Path)>,
-  game_data: Res<GameData>,
-  map: Res<Assets<Map>>,
-  time: Res<Time>,
+  map: Res<Map>,
+  fixed_time: Res<FixedTime>,
 ) {
-  let Some(map) = map.get(&game_data.map)
-    else { return; };
-
   for (mut movement, mut transform, mut path) in &mut enemies {
-    if path.index >= map.checkpoints.len() {
+    let checkpoints = &map.checkpoints[path.id];
+    if path.index >= checkpoints.len() {
       continue;
     }
 
-    let distance = map.checkpoints[path.index] - transform.translation;
+    let distance = checkpoints[path.index] - transform.translation;
     if distance == Vec3::ZERO {
       path.index += 1;
       continue;
     }
-    let enemy_movement = distance.normalize() * movement.speed * time.delta_seconds();
+    let enemy_movement =
+      distance.normalize() * movement.speed * fixed_time.period.as_secs_f32();
 
     if enemy_movement.length() > distance.length() {
-      transform.translation = map.checkpoints[path.index];
+      transform.translation = checkpoints[path.index];
       movement.distance_travelled += distance.length();
-      movement.direction = map.checkpoints[path.index] - transform.translation;
+      movement.direction = checkpoints[path.index] - transform.translation;
       path.index += 1;
     } else {
       movement.distance_travelled += enemy_movement.length();
-      movement.direction = map.checkpoints[path.index] - transform.translation;
+      movement.direction = checkpoints[path.index] - transform.translation;
       transform.translation += enemy_movement;
     }
   }
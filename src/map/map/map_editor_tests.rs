@@ -0,0 +1,101 @@
+use super::*;
+
+fn editor_with_brush(brush: Brush, path_id: usize) -> MapEditor {
+    let mut editor = MapEditor::new(4, 3);
+    editor.brush = brush;
+    editor.path_id = path_id;
+    editor
+}
+
+#[test]
+fn test_new_map_is_grass() {
+    let editor = MapEditor::new(4, 3);
+    assert_eq!(editor.map.tiles.len(), 3);
+    assert!(editor.map.tiles.iter().all(|row| row == &vec![Tile::Grass; 4]));
+}
+
+#[test]
+fn test_paint_with_brush() {
+    let mut editor = editor_with_brush(Brush::Water, 0);
+    assert!(editor.paint(1, 2));
+    assert_eq!(editor.map.tiles[2][1], Tile::Water);
+    // Painting the same tile again changes nothing
+    assert!(!editor.paint(1, 2));
+}
+
+#[test]
+fn test_paint_outside_of_shrunk_map_is_ignored() {
+    let mut editor = editor_with_brush(Brush::Water, 0);
+    editor.resize(2, 2);
+    assert!(!editor.paint(3, 2));
+    assert!(!editor.set_tile(1, 2, Tile::Grass));
+    assert_eq!(editor.map.tiles.len(), 2);
+}
+
+#[test]
+fn test_paint_path_shares_tiles_between_paths() {
+    let mut editor = editor_with_brush(Brush::Path, 1);
+    editor.paint(0, 0);
+    assert_eq!(editor.map.tiles[0][0], Tile::Path(vec![1]));
+
+    editor.path_id = 0;
+    assert!(editor.paint(0, 0));
+    assert_eq!(editor.map.tiles[0][0], Tile::Path(vec![0, 1]));
+    assert!(!editor.paint(0, 0));
+}
+
+#[test]
+fn test_resize_keeps_tiles_and_adds_grass() {
+    let mut editor = editor_with_brush(Brush::Spawn, 0);
+    editor.paint(0, 0);
+    editor.resize(6, 2);
+
+    assert_eq!((editor.map.width, editor.map.height), (6, 2));
+    assert_eq!(editor.map.tiles.len(), 2);
+    assert!(editor.map.tiles.iter().all(|row| row.len() == 6));
+    assert_eq!(editor.map.tiles[0][0], Tile::Spawn);
+    assert_eq!(editor.map.tiles[1][5], Tile::Grass);
+}
+
+#[test]
+fn test_resize_is_clamped() {
+    let mut editor = MapEditor::new(4, 3);
+    editor.resize(0, MAX_MAP_HEIGHT + 5);
+    assert_eq!((editor.map.width, editor.map.height), (1, MAX_MAP_HEIGHT));
+}
+
+#[test]
+fn test_prepared_map_previews_checkpoints() {
+    // Spawn on the left, a path through the middle row and the end on the right
+    let mut editor = MapEditor::new(4, 3);
+    editor.map.tiles[1] = vec![Tile::Spawn, Tile::Path(vec![0]), Tile::Path(vec![0]), Tile::End];
+
+    let map = editor.prepared_map();
+    assert_eq!(map.checkpoints.len(), 1);
+    assert_eq!(map.checkpoints[0].len(), 4);
+    // The edited map keeps its rows in file order
+    assert!(editor.map.checkpoints.is_empty());
+}
+
+#[test]
+fn test_saved_map_loads_back() {
+    let mut editor = editor_with_brush(Brush::Path, 2);
+    editor.paint(3, 1);
+
+    let ron = editor.to_ron().unwrap();
+    // One row per line
+    assert!(ron.contains("[Grass, Grass, Grass, Path([2])]"));
+    let map: Map = ron::from_str(&ron).unwrap();
+    assert_eq!(map.tiles, editor.map.tiles);
+    assert_eq!((map.width, map.height, map.tile_size), (4, 3, 80));
+}
+
+#[test]
+fn test_new_map_path_skips_existing_files() {
+    let folder = std::env::temp_dir().join(format!("map_editor_test_{}", std::process::id()));
+    fs::create_dir_all(&folder).unwrap();
+    fs::write(folder.join("custom1.map.ron"), "").unwrap();
+
+    assert_eq!(new_map_path(&folder), folder.join("custom2.map.ron"));
+    fs::remove_dir_all(&folder).unwrap();
+}
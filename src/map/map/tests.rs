@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
     use bevy::math::Vec3;
-    use crate::{Coordinate, Map, Point};
+    use crate::{Map, Point, Tile};
 
     fn make_map(tile_size: usize) -> Map {
         Map {
@@ -55,7 +55,7 @@ mod tests {
 
     #[test]
     fn test_create_checkpoints_straight_path() {
-        let mut map = make_map(80);
+        let map = make_map(80);
         let spawn = Point { x: 0, y: 0 };
         let end = Point { x: 4, y: 0 };
         let path_tiles = vec![
@@ -63,68 +63,224 @@ mod tests {
             Point { x: 2, y: 0 },
             Point { x: 3, y: 0 },
         ];
-        map.create_checkpoints(path_tiles, spawn, end);
-        assert_eq!(map.checkpoints.len(), 5);
-        assert_eq!(map.checkpoints[0], Vec3::new(0.0, 0.0, 0.0));
-        assert_eq!(map.checkpoints[1], Vec3::new(80.0, 40.0, 0.0));
-        assert_eq!(map.checkpoints[4], Vec3::new(320.0, 40.0, 0.0));
+        let checkpoints = map.create_checkpoints(path_tiles, spawn, end);
+        assert_eq!(checkpoints.len(), 5);
+        assert_eq!(checkpoints[0], Vec3::new(0.0, 0.0, 0.0));
+        assert_eq!(checkpoints[1], Vec3::new(80.0, 40.0, 0.0));
+        assert_eq!(checkpoints[4], Vec3::new(320.0, 40.0, 0.0));
     }
 
     #[test]
     fn test_create_checkpoints_no_path_tiles() {
-        let mut map = make_map(80);
+        let map = make_map(80);
         let spawn = Point { x: 0, y: 0 };
         let end = Point { x: 1, y: 0 };
-        map.create_checkpoints(vec![], spawn, end);
-        assert_eq!(map.checkpoints.len(), 2);
-        assert_eq!(map.checkpoints[0], Vec3::new(0.0, 0.0, 0.0));
-        assert_eq!(map.checkpoints[1], Vec3::new(80.0, 40.0, 0.0));
+        let checkpoints = map.create_checkpoints(vec![], spawn, end);
+        assert_eq!(checkpoints.len(), 2);
+        assert_eq!(checkpoints[0], Vec3::new(0.0, 0.0, 0.0));
+        assert_eq!(checkpoints[1], Vec3::new(80.0, 40.0, 0.0));
     }
 
     #[test]
     fn test_create_checkpoints_unordered_path_tiles() {
-        let mut map = make_map(80);
+        let map = make_map(80);
         let spawn = Point { x: 0, y: 0 };
         let end = Point { x: 3, y: 0 };
         let path_tiles = vec![
             Point { x: 2, y: 0 },
             Point { x: 1, y: 0 },
         ];
-        map.create_checkpoints(path_tiles, spawn, end);
-        assert_eq!(map.checkpoints.len(), 4);
-        assert_eq!(map.checkpoints[1], Vec3::new(80.0, 40.0, 0.0));
-        assert_eq!(map.checkpoints[2], Vec3::new(160.0, 40.0, 0.0));
+        let checkpoints = map.create_checkpoints(path_tiles, spawn, end);
+        assert_eq!(checkpoints.len(), 4);
+        assert_eq!(checkpoints[1], Vec3::new(80.0, 40.0, 0.0));
+        assert_eq!(checkpoints[2], Vec3::new(160.0, 40.0, 0.0));
     }
 
     #[test]
     fn test_create_checkpoints_with_turn() {
-        let mut map = make_map(80);
+        let map = make_map(80);
         let spawn = Point { x: 0, y: 0 };
         let end = Point { x: 1, y: 2 };
         let path_tiles = vec![
             Point { x: 1, y: 0 },
             Point { x: 1, y: 1 },
         ];
-        map.create_checkpoints(path_tiles, spawn, end);
-        assert_eq!(map.checkpoints.len(), 4);
-        assert_eq!(map.checkpoints[0], Vec3::new(0.0, 0.0, 0.0));
-        assert_eq!(map.checkpoints[1], Vec3::new(80.0, 40.0, 0.0));
-        assert_eq!(map.checkpoints[2], Vec3::new(80.0, 120.0, 0.0));
-        assert_eq!(map.checkpoints[3], Vec3::new(80.0, 200.0, 0.0));
+        let checkpoints = map.create_checkpoints(path_tiles, spawn, end);
+        assert_eq!(checkpoints.len(), 4);
+        assert_eq!(checkpoints[0], Vec3::new(0.0, 0.0, 0.0));
+        assert_eq!(checkpoints[1], Vec3::new(80.0, 40.0, 0.0));
+        assert_eq!(checkpoints[2], Vec3::new(80.0, 120.0, 0.0));
+        assert_eq!(checkpoints[3], Vec3::new(80.0, 200.0, 0.0));
     }
 
     #[test]
     fn test_create_checkpoints_different_tile_size() {
-        let mut map = Map { tile_size: 10, ..Default::default() };
+        let map = Map { tile_size: 10, ..Default::default() };
         let spawn = Point { x: 0, y: 0 };
         let end = Point { x: 2, y: 1 };
         let path_tiles = vec![
             Point { x: 1, y: 0 },
             Point { x: 1, y: 1 },
         ];
-        map.create_checkpoints(path_tiles, spawn, end);
-        assert_eq!(map.checkpoints.len(), 4);
-        assert_eq!(map.checkpoints[1], Vec3::new(10.0, 5.0, 0.0));
-        assert_eq!(map.checkpoints[3], Vec3::new(20.0, 15.0, 0.0));
+        let checkpoints = map.create_checkpoints(path_tiles, spawn, end);
+        assert_eq!(checkpoints.len(), 4);
+        assert_eq!(checkpoints[1], Vec3::new(10.0, 5.0, 0.0));
+        assert_eq!(checkpoints[3], Vec3::new(20.0, 15.0, 0.0));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_prepare_flips_rows_and_creates_checkpoints() {
+        let mut map = Map {
+            width: 3,
+            height: 2,
+            tiles: vec![
+                vec![Tile::Grass, Tile::Grass, Tile::Water],
+                vec![Tile::Spawn, Tile::Path(vec![0]), Tile::End],
+            ],
+            tile_size: 80,
+            checkpoints: vec![vec![Vec3::ONE]],
+        };
+        map.prepare();
+        assert_eq!(map.tiles[0][0], Tile::Spawn);
+        assert_eq!(map.tiles[1][2], Tile::Water);
+        assert_eq!(map.checkpoints.len(), 1);
+        assert_eq!(map.checkpoints[0].len(), 3);
+        assert_eq!(map.checkpoints[0][0], Vec3::new(0.0, -160.0, 0.0));
+        assert_eq!(map.checkpoints[0][2], Vec3::new(160.0, 40.0, 0.0));
+    }
+
+    #[test]
+    fn test_prepare_creates_checkpoints_per_path() {
+        // Two separate paths going in opposite directions
+        let mut map = Map {
+            width: 4,
+            height: 3,
+            tiles: vec![
+                vec![Tile::End, Tile::Path(vec![1]), Tile::Path(vec![1]), Tile::Spawn],
+                vec![Tile::Grass, Tile::Grass, Tile::Grass, Tile::Grass],
+                vec![Tile::Spawn, Tile::Path(vec![0]), Tile::Path(vec![0]), Tile::End],
+            ],
+            tile_size: 10,
+            checkpoints: vec![],
+        };
+        map.prepare();
+        assert_eq!(map.path_count(), 2);
+        assert_eq!(map.checkpoints[0].len(), 4);
+        assert_eq!(map.checkpoints[0][1], Vec3::new(10.0, 5.0, 0.0));
+        assert_eq!(map.checkpoints[0][3], Vec3::new(30.0, 5.0, 0.0));
+        assert_eq!(map.checkpoints[1].len(), 4);
+        assert_eq!(map.checkpoints[1][1], Vec3::new(20.0, 25.0, 0.0));
+        assert_eq!(map.checkpoints[1][3], Vec3::new(0.0, 25.0, 0.0));
+    }
+
+    #[test]
+    fn test_prepare_paths_merge_into_shared_tiles() {
+        // Two spawns whose paths join and share the last tiles before the end
+        let mut map = Map {
+            width: 4,
+            height: 3,
+            tiles: vec![
+                vec![Tile::Spawn, Tile::Path(vec![1]), Tile::Grass, Tile::Grass],
+                vec![Tile::Grass, Tile::Path(vec![0, 1]), Tile::Path(vec![0, 1]), Tile::End],
+                vec![Tile::Spawn, Tile::Path(vec![0]), Tile::Grass, Tile::Grass],
+            ],
+            tile_size: 10,
+            checkpoints: vec![],
+        };
+        map.prepare();
+        assert_eq!(map.path_count(), 2);
+        for checkpoints in &map.checkpoints {
+            assert_eq!(checkpoints.len(), 5);
+            assert_eq!(checkpoints[2], Vec3::new(10.0, 15.0, 0.0));
+            assert_eq!(checkpoints[4], Vec3::new(30.0, 15.0, 0.0));
+        }
+        assert_eq!(map.checkpoints[0][1], Vec3::new(10.0, 5.0, 0.0));
+        assert_eq!(map.checkpoints[1][1], Vec3::new(10.0, 25.0, 0.0));
+    }
+
+    #[test]
+    fn test_prepare_path_forks_from_one_spawn() {
+        let mut map = Map {
+            width: 4,
+            height: 3,
+            tiles: vec![
+                vec![Tile::Grass, Tile::Path(vec![1]), Tile::Path(vec![1]), Tile::End],
+                vec![Tile::Spawn, Tile::Path(vec![0, 1]), Tile::Grass, Tile::Grass],
+                vec![Tile::Grass, Tile::Path(vec![0]), Tile::Path(vec![0]), Tile::End],
+            ],
+            tile_size: 10,
+            checkpoints: vec![],
+        };
+        map.prepare();
+        assert_eq!(map.checkpoints[0][0], map.checkpoints[1][0]);
+        assert_eq!(map.checkpoints[0][1], map.checkpoints[1][1]);
+        assert_eq!(map.checkpoints[0][4], Vec3::new(30.0, 5.0, 0.0));
+        assert_eq!(map.checkpoints[1][4], Vec3::new(30.0, 25.0, 0.0));
+    }
+
+    #[test]
+    fn test_tile_at_world_position() {
+        let map = Map {
+            width: 2,
+            height: 2,
+            tiles: vec![
+                vec![Tile::Grass, Tile::Water],
+                vec![Tile::Spawn, Tile::Path(vec![0])],
+            ],
+            tile_size: 80,
+            checkpoints: vec![],
+        };
+        assert_eq!(map.tile_at(Vec3::new(0.0, 0.0, 0.5)), Some(&Tile::Grass));
+        assert_eq!(map.tile_at(Vec3::new(-39.0, 39.0, 0.5)), Some(&Tile::Grass));
+        assert_eq!(map.tile_at(Vec3::new(41.0, 0.0, 0.5)), Some(&Tile::Water));
+        assert_eq!(map.tile_at(Vec3::new(80.0, 80.0, 0.5)), Some(&Tile::Path(vec![0])));
+    }
+
+    #[test]
+    fn test_tile_at_outside_map() {
+        let map = Map {
+            width: 1,
+            height: 1,
+            tiles: vec![vec![Tile::Grass]],
+            tile_size: 80,
+            checkpoints: vec![],
+        };
+        assert_eq!(map.tile_at(Vec3::new(-41.0, 0.0, 0.0)), None);
+        assert_eq!(map.tile_at(Vec3::new(0.0, -41.0, 0.0)), None);
+        assert_eq!(map.tile_at(Vec3::new(40.0, 0.0, 0.0)), None);
+    }
+
+    #[test]
+    fn test_thumbnail_has_one_pixel_per_tile() {
+        let map = Map {
+            width: 2,
+            height: 2,
+            tiles: vec![
+                vec![Tile::Grass, Tile::Water],
+                vec![Tile::Spawn, Tile::Path(vec![0])],
+            ],
+            tile_size: 80,
+            checkpoints: vec![],
+        };
+        let thumbnail = map.thumbnail();
+        assert_eq!(thumbnail.size().x, 2.0);
+        assert_eq!(thumbnail.size().y, 2.0);
+        assert_eq!(thumbnail.data.len(), 16);
+        assert_eq!(thumbnail.data[0..4], Tile::Grass.thumbnail_color());
+        assert_eq!(thumbnail.data[4..8], Tile::Water.thumbnail_color());
+        assert_eq!(thumbnail.data[12..16], Tile::Path(vec![0]).thumbnail_color());
+    }
+
+    #[test]
+    fn test_thumbnail_missing_tiles_are_empty() {
+        let map = Map {
+            width: 2,
+            height: 1,
+            tiles: vec![vec![Tile::Grass]],
+            tile_size: 80,
+            checkpoints: vec![],
+        };
+        let thumbnail = map.thumbnail();
+        assert_eq!(thumbnail.data[4..8], Tile::Empty.thumbnail_color());
+    }
+}
@@ -0,0 +1,146 @@
+use super::*;
+
+// Map from rows of characters: `.` grass, `S` spawn, `E` end, digits are path ids
+// and `x` is shared by paths 0 and 1
+fn map(rows: &[&str]) -> Map {
+    let tiles = rows
+        .iter()
+        .map(|row| {
+            row.chars()
+                .map(|tile| match tile {
+                    'S' => Tile::Spawn,
+                    'E' => Tile::End,
+                    'x' => Tile::Path(vec![0, 1]),
+                    id if id.is_ascii_digit() => {
+                        Tile::Path(vec![id.to_digit(10).unwrap() as usize])
+                    }
+                    _ => Tile::Grass,
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    Map {
+        width: tiles.first().map_or(0, |row| row.len()),
+        height: tiles.len(),
+        tiles,
+        tile_size: 80,
+        checkpoints: vec![],
+    }
+}
+
+fn tiles(map: &Map) -> Vec<Point> {
+    let mut tiles = vec![];
+    for (y, row) in map.tiles.iter().enumerate() {
+        for (x, tile) in row.iter().enumerate() {
+            if matches!(tile, Tile::Path(_)) {
+                tiles.push(Point { x, y });
+            }
+        }
+    }
+    tiles
+}
+
+fn errors(map: &Map) -> Vec<MapError> {
+    map.validate().err().unwrap_or_default()
+}
+
+#[test]
+fn test_levels_are_valid() {
+    for level in ["level1", "level2", "level3"] {
+        let ron = std::fs::read_to_string(format!("assets/data/{}.map.ron", level)).unwrap();
+        let map: Map = ron::from_str(&ron).unwrap();
+        assert_eq!(map.validate(), Ok(()), "{}", level);
+    }
+}
+
+#[test]
+fn test_straight_path_is_valid() {
+    assert_eq!(map(&["....", "S00E", "...."]).validate(), Ok(()));
+}
+
+#[test]
+fn test_forking_paths_are_valid() {
+    // Both paths share the spawn and the tiles next to it
+    let map = map(&["..000E", "Sxx...", "..111E"]);
+    assert_eq!(map.validate(), Ok(()));
+}
+
+#[test]
+fn test_row_lengths_must_match_the_size() {
+    let mut map = map(&["S0E", "..."]);
+    map.tiles[1].pop();
+    map.height = 3;
+
+    assert_eq!(
+        errors(&map),
+        vec![
+            MapError::RowCount { expected: 3, found: 2 },
+            MapError::RowLength { row: 1, expected: 3, found: 2 },
+        ]
+    );
+}
+
+#[test]
+fn test_map_needs_a_path() {
+    assert_eq!(errors(&map(&["S.E"])), vec![MapError::NoPaths]);
+}
+
+#[test]
+fn test_path_ids_cannot_be_skipped() {
+    assert_eq!(errors(&map(&["S1E"])), vec![MapError::EmptyPath { path: 0 }]);
+}
+
+#[test]
+fn test_path_needs_one_spawn_and_one_end() {
+    assert_eq!(errors(&map(&["00E"])), vec![MapError::SpawnCount { path: 0, found: 0 }]);
+    assert_eq!(errors(&map(&["S00E", ".S.."])), vec![MapError::SpawnCount { path: 0, found: 2 }]);
+    assert_eq!(errors(&map(&["S00", "..."])), vec![MapError::EndCount { path: 0, found: 0 }]);
+}
+
+#[test]
+fn test_path_cannot_branch() {
+    assert_eq!(
+        errors(&map(&[".000.", "S0.0E", ".000."])),
+        vec![MapError::Branch { path: 0, column: 1, row: 1 }]
+    );
+}
+
+#[test]
+fn test_path_cannot_stop_before_its_end() {
+    assert_eq!(
+        errors(&map(&["S00..", "....E", "....0"])),
+        vec![MapError::DeadEnd { path: 0, column: 2, row: 0 }]
+    );
+}
+
+#[test]
+fn test_path_tiles_must_be_connected() {
+    assert_eq!(
+        errors(&map(&["S0E", "...", ".0."])),
+        vec![MapError::Disconnected { path: 0, column: 1, row: 2 }]
+    );
+}
+
+#[test]
+fn test_errors_describe_where_the_problem_is() {
+    let error = MapError::DeadEnd { path: 1, column: 4, row: 2 };
+    assert_eq!(
+        error.to_string(),
+        "path 1 stops at column 4, row 2 without reaching its end"
+    );
+}
+
+#[test]
+fn test_path_can_cross_itself() {
+    // Up from the spawn, around the loop and straight through the tile it passed before
+    let map = map(&[".000", ".0.0", "0000", "E0..", ".S.."]);
+    assert_eq!(map.validate(), Ok(()));
+
+    let (walked, branch) = walk_path(Point { x: 1, y: 4 }, &tiles(&map));
+    assert_eq!(branch, None);
+    assert_eq!(walked.len(), 11);
+    assert_eq!(walked[1], Point { x: 1, y: 2 });
+    assert_eq!(walked[8], Point { x: 2, y: 2 });
+    assert_eq!(walked[9], Point { x: 1, y: 2 });
+    assert_eq!(walked[10], Point { x: 0, y: 2 });
+}
@@ -0,0 +1,452 @@
+use bevy::prelude::*;
+use std::error::Error;
+use std::fs;
+use std::path::{Path as FilePath, PathBuf};
+
+use crate::assets::*;
+use crate::map::*;
+use crate::{level_name, window_to_world_pos, GameState, Level, Levels};
+
+pub struct MapEditorPlugin;
+
+impl Plugin for MapEditorPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .add_system(setup_map_editor.in_schedule(OnEnter(GameState::MapEditor)))
+      .add_systems(
+        (
+          select_brush,
+          resize_map,
+          // Tiles are found on the rendered map, which doesn't exist before the first update.
+          // It only catches up with a resize after `update_editor_map`, so tiles are painted
+          // before the edited map changes size
+          paint_tiles
+            .before(resize_map)
+            .run_if(resource_exists::<Map>()),
+          save_map,
+          leave_map_editor,
+        )
+          .before(update_editor_map)
+          .in_set(OnUpdate(GameState::MapEditor)),
+      )
+      // The edited map is rendered and its paths are computed again after every change
+      .add_systems(
+        (
+          update_editor_map,
+          apply_system_buffers,
+          render_map,
+          show_checkpoints,
+          update_map_editor_ui,
+        )
+          .chain()
+          .distributive_run_if(resource_changed::<MapEditor>())
+          .in_set(OnUpdate(GameState::MapEditor)),
+      )
+      .add_system(despawn_map_editor.in_schedule(OnExit(GameState::MapEditor)));
+  }
+}
+
+// Folder new maps are saved to, the levels are every map in it
+pub const MAP_FOLDER: &str = "assets/data";
+
+pub const MAX_MAP_WIDTH: usize = 32;
+pub const MAX_MAP_HEIGHT: usize = 18;
+
+// Colors of the checkpoint markers, by path id
+const PATH_COLORS: [Color; 4] = [Color::RED, Color::BLUE, Color::FUCHSIA, Color::ORANGE];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Brush {
+  Grass,
+  Water,
+  Path,
+  Spawn,
+  End,
+}
+
+#[derive(Resource)]
+pub struct MapEditor {
+  // Map being edited, with its rows in file order - the first row is the top of the map
+  pub map: Map,
+  pub brush: Brush,
+  // Path that `Brush::Path` adds tiles to
+  pub path_id: usize,
+  // Result of the last save
+  pub status: String,
+}
+
+#[derive(Component)]
+pub struct MapEditorUIRoot;
+
+#[derive(Component)]
+pub struct MapEditorText;
+
+#[derive(Component)]
+pub struct CheckpointMarker;
+
+impl MapEditor {
+  // Grass map of the same size as the levels
+  pub fn new(width: usize, height: usize) -> Self {
+    Self {
+      map: Map {
+        width,
+        height,
+        tiles: vec![vec![Tile::Grass; width]; height],
+        tile_size: 80,
+        checkpoints: vec![],
+      },
+      brush: Brush::Path,
+      path_id: 0,
+      status: String::new(),
+    }
+  }
+
+  // Paint the tile with the brush. Path tiles that already belong to other paths are shared
+  // with the current one, which is how paths fork and merge. Returns whether the tile changed,
+  // tiles outside of the map never do
+  pub fn paint(&mut self, column: usize, row: usize) -> bool {
+    let Some(current) = self.map.tiles.get(row).and_then(|tiles| tiles.get(column))
+      else { return false; };
+    let tile = match (self.brush, current) {
+      (Brush::Path, Tile::Path(ids)) if ids.contains(&self.path_id) => return false,
+      (Brush::Path, Tile::Path(ids)) => {
+        let mut ids = ids.clone();
+        ids.push(self.path_id);
+        ids.sort();
+        Tile::Path(ids)
+      }
+      (Brush::Path, _) => Tile::Path(vec![self.path_id]),
+      (Brush::Grass, _) => Tile::Grass,
+      (Brush::Water, _) => Tile::Water,
+      (Brush::Spawn, _) => Tile::Spawn,
+      (Brush::End, _) => Tile::End,
+    };
+    self.set_tile(column, row, tile)
+  }
+
+  pub fn set_tile(&mut self, column: usize, row: usize, tile: Tile) -> bool {
+    let Some(current) = self.map.tiles.get_mut(row).and_then(|tiles| tiles.get_mut(column))
+      else { return false; };
+    if *current == tile {
+      return false;
+    }
+    *current = tile;
+    true
+  }
+
+  // Rows and columns are added and removed at the bottom and on the right, as grass
+  pub fn resize(&mut self, width: usize, height: usize) {
+    let width = width.clamp(1, MAX_MAP_WIDTH);
+    let height = height.clamp(1, MAX_MAP_HEIGHT);
+
+    self.map.tiles.resize(height, vec![]);
+    for row in &mut self.map.tiles {
+      row.resize(width, Tile::Grass);
+    }
+    self.map.width = width;
+    self.map.height = height;
+  }
+
+  // Map as it is played, with its rows flipped and its checkpoints computed
+  pub fn prepared_map(&self) -> Map {
+    let mut map = self.map.clone();
+    map.prepare();
+    map
+  }
+
+  // One row of tiles per line, like the hand-written maps
+  pub fn to_ron(&self) -> Result<String, Box<dyn Error>> {
+    let config = ron::ser::PrettyConfig::default().depth_limit(2);
+    Ok(ron::ser::to_string_pretty(&self.map, config)?)
+  }
+
+  // Write the map to a new file in the folder, returns its path
+  pub fn save(&self, folder: &FilePath) -> Result<PathBuf, Box<dyn Error>> {
+    let path = new_map_path(folder);
+    fs::write(&path, self.to_ron()?)?;
+    Ok(path)
+  }
+}
+
+// First `customN.map.ron` that doesn't exist yet, existing maps are never overwritten
+pub fn new_map_path(folder: &FilePath) -> PathBuf {
+  (1..)
+    .map(|n| folder.join(format!("custom{}.map.ron", n)))
+    .find(|path| !path.exists())
+    .unwrap()
+}
+
+fn setup_map_editor(mut commands: Commands, assets: Res<GameAssets>) {
+  commands.insert_resource(MapEditor::new(16, 9));
+  spawn_map_editor_ui(&mut commands, &assets);
+}
+
+fn select_brush(keys: Res<Input<KeyCode>>, mut editor: ResMut<MapEditor>) {
+  let brushes = [
+    (KeyCode::Key1, Brush::Grass),
+    (KeyCode::Key2, Brush::Water),
+    (KeyCode::Key3, Brush::Path),
+    (KeyCode::Key4, Brush::Spawn),
+    (KeyCode::Key5, Brush::End),
+  ];
+  for (key, brush) in brushes {
+    if keys.just_pressed(key) {
+      editor.brush = brush;
+    }
+  }
+
+  if keys.just_pressed(KeyCode::Q) && editor.path_id > 0 {
+    editor.path_id -= 1;
+  } else if keys.just_pressed(KeyCode::E) {
+    editor.path_id += 1;
+  }
+}
+
+fn resize_map(keys: Res<Input<KeyCode>>, mut editor: ResMut<MapEditor>) {
+  let (width, height) = (editor.map.width, editor.map.height);
+
+  if keys.just_pressed(KeyCode::Right) {
+    editor.resize(width + 1, height);
+  } else if keys.just_pressed(KeyCode::Left) {
+    editor.resize(width.saturating_sub(1), height);
+  } else if keys.just_pressed(KeyCode::Up) {
+    editor.resize(width, height + 1);
+  } else if keys.just_pressed(KeyCode::Down) {
+    editor.resize(width, height.saturating_sub(1));
+  }
+}
+
+// Left mouse button paints with the brush, right mouse button turns tiles back into grass
+fn paint_tiles(
+  mouse: Res<Input<MouseButton>>,
+  windows: Query<&Window>,
+  camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+  map: Res<Map>,
+  mut editor: ResMut<MapEditor>,
+) {
+  let painting = mouse.pressed(MouseButton::Left);
+  if !painting && !mouse.pressed(MouseButton::Right) {
+    return;
+  }
+
+  let window = windows.single();
+  let (camera, camera_transform) = camera_query.single();
+  let Some(cursor_position) = window.cursor_position()
+    else { return; };
+  let position = window_to_world_pos(window, cursor_position, camera, camera_transform);
+  // The rendered map is flipped, its first row is the last row of the edited map
+  let Some(point) = map.tile_coordinate(position)
+    else { return; };
+  let row = map.height - 1 - point.y;
+
+  // Only a tile that actually changes renders the map again
+  let changed = if painting {
+    editor.bypass_change_detection().paint(point.x, row)
+  } else {
+    editor.bypass_change_detection().set_tile(point.x, row, Tile::Grass)
+  };
+  if changed {
+    editor.set_changed();
+  }
+}
+
+// Ctrl+S saves the map into the data folder and adds it to the levels right away
+fn save_map(
+  keys: Res<Input<KeyCode>>,
+  mut editor: ResMut<MapEditor>,
+  mut levels: ResMut<Levels>,
+  mut maps: ResMut<Assets<Map>>,
+  mut images: ResMut<Assets<Image>>,
+) {
+  let control = keys.pressed(KeyCode::LControl) || keys.pressed(KeyCode::RControl);
+  if !(control && keys.just_pressed(KeyCode::S)) {
+    return;
+  }
+
+  match editor.save(FilePath::new(MAP_FOLDER)) {
+    Ok(path) => {
+      let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+      let name = level_name(file_name).unwrap_or(file_name).to_string();
+
+      levels.levels.push(Level {
+        name: name.clone(),
+        thumbnail: images.add(editor.map.thumbnail()),
+        map: maps.add(editor.map.clone()),
+        errors: editor.map.validate().err().unwrap_or_default(),
+      });
+      levels.levels.sort_by(|a, b| a.name.cmp(&b.name));
+      editor.status = format!("Saved {}", path.display());
+    }
+    Err(error) => {
+      error!("Failed to save map: {}", error);
+      editor.status = format!("Failed to save map: {}", error);
+    }
+  }
+}
+
+fn leave_map_editor(keys: Res<Input<KeyCode>>, mut game_state: ResMut<NextState<GameState>>) {
+  if keys.just_pressed(KeyCode::Escape) {
+    game_state.set(GameState::MainMenu);
+  }
+}
+
+// Replace the rendered map with the edited one and fit it on the screen
+#[allow(clippy::type_complexity)]
+fn update_editor_map(
+  mut commands: Commands,
+  editor: Res<MapEditor>,
+  rendered: Query<Entity, Or<(With<TileMap>, With<CheckpointMarker>)>>,
+  mut camera: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+) {
+  for entity in &rendered {
+    commands.entity(entity).despawn_recursive();
+  }
+
+  let map = editor.prepared_map();
+  let (mut camera_transform, mut projection) = camera.single_mut();
+  let (width, height) = (
+    (map.width * map.tile_size) as f32,
+    (map.height * map.tile_size) as f32,
+  );
+  camera_transform.translation.x = (map.width as f32 / 2. - 0.5) * map.tile_size as f32;
+  camera_transform.translation.y = (map.height as f32 / 2. - 0.5) * map.tile_size as f32;
+  // Leave room for the controls above and below the map
+  projection.scale = (width / 1280.).max(height / 720.) * 1.25;
+
+  commands.insert_resource(map);
+}
+
+// Numbered markers on the checkpoints of every path, in the order enemies walk them
+fn show_checkpoints(mut commands: Commands, map: Res<Map>, assets: Res<GameAssets>) {
+  for (path_id, checkpoints) in map.checkpoints.iter().enumerate() {
+    let color = PATH_COLORS[path_id % PATH_COLORS.len()];
+
+    for (index, checkpoint) in checkpoints.iter().enumerate() {
+      commands
+        .spawn(SpriteBundle {
+          sprite: Sprite {
+            color,
+            custom_size: Some(Vec2::splat(map.tile_size as f32 / 4.)),
+            ..default()
+          },
+          transform: Transform::from_translation(checkpoint.truncate().extend(1.)),
+          ..default()
+        })
+        .with_children(|commands| {
+          commands.spawn(Text2dBundle {
+            text: Text::from_section(
+              index.to_string(),
+              TextStyle {
+                font: assets.font.clone(),
+                font_size: 16.,
+                color: Color::WHITE,
+              },
+            ),
+            transform: Transform::from_xyz(0., 0., 0.1),
+            ..default()
+          });
+        })
+        .insert(CheckpointMarker)
+        .insert(Name::new(format!("Checkpoint {} {}", path_id, index)));
+    }
+  }
+}
+
+fn update_map_editor_ui(
+  editor: Res<MapEditor>,
+  map: Res<Map>,
+  mut editor_ui: Query<&mut Text, With<MapEditorText>>,
+) {
+  let brush = match editor.brush {
+    Brush::Path => format!("Path {}", editor.path_id),
+    brush => format!("{:?}", brush),
+  };
+  let paths = map
+    .checkpoints
+    .iter()
+    .enumerate()
+    .map(|(id, checkpoints)| format!("path {}: {} checkpoints", id, checkpoints.len()))
+    .collect::<Vec<_>>()
+    .join(", ");
+
+  // Problems that would keep the map from being played
+  let errors = editor
+    .map
+    .validate()
+    .err()
+    .unwrap_or_default()
+    .iter()
+    .map(|error| format!("\n{}", error))
+    .collect::<String>();
+
+  for mut text in &mut editor_ui {
+    text.sections[0].value = format!(
+      "Brush: {}   Size: {} x {}   {}   {}",
+      brush, editor.map.width, editor.map.height, paths, editor.status
+    );
+    text.sections[1].value = errors.clone();
+  }
+}
+
+#[allow(clippy::type_complexity)]
+fn despawn_map_editor(
+  mut commands: Commands,
+  entities: Query<
+    Entity,
+    Or<(With<TileMap>, With<CheckpointMarker>, With<MapEditorUIRoot>)>,
+  >,
+  mut camera: Query<&mut OrthographicProjection, With<MainCamera>>,
+) {
+  for entity in &entities {
+    commands.entity(entity).despawn_recursive();
+  }
+  commands.remove_resource::<MapEditor>();
+  commands.remove_resource::<Map>();
+  camera.single_mut().scale = 1.;
+}
+
+fn spawn_map_editor_ui(commands: &mut Commands, assets: &GameAssets) {
+  let text_style = TextStyle {
+    font: assets.font.clone(),
+    font_size: 22.,
+    color: Color::WHITE,
+  };
+
+  commands
+    .spawn(NodeBundle {
+      style: Style {
+        size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+        position_type: PositionType::Absolute,
+        justify_content: JustifyContent::SpaceBetween,
+        flex_direction: FlexDirection::Column,
+        padding: UiRect::all(Val::Px(10.)),
+        ..default()
+      },
+      ..default()
+    })
+    .insert(MapEditorUIRoot)
+    .insert(Name::new("MapEditorUI"))
+    .with_children(|commands| {
+      commands
+        .spawn(TextBundle::from_sections([
+          TextSection::new("", text_style.clone()),
+          TextSection::new(
+            "",
+            TextStyle {
+              color: Color::CRIMSON,
+              ..text_style.clone()
+            },
+          ),
+        ]))
+        .insert(MapEditorText);
+      commands.spawn(TextBundle::from_section(
+        "1-5: Grass, Water, Path, Spawn, End   Q/E: path   Arrows: resize   \
+         Left click: paint   Right click: erase   Ctrl+S: save   Esc: main menu",
+        text_style,
+      ));
+    });
+}
+
+#[cfg(test)]
+#[path = "map/map_editor_tests.rs"]
+mod tests;
@@ -0,0 +1,179 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::map::*;
+
+// Problem that keeps a map from being played. Rows are counted from the top and columns
+// from the left, like the tiles in the map files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapError {
+  RowCount { expected: usize, found: usize },
+  RowLength { row: usize, expected: usize, found: usize },
+  NoPaths,
+  EmptyPath { path: usize },
+  SpawnCount { path: usize, found: usize },
+  EndCount { path: usize, found: usize },
+  // A path tile with several next tiles, enemies can't tell which way to go
+  Branch { path: usize, column: usize, row: usize },
+  // The path stops before reaching its end
+  DeadEnd { path: usize, column: usize, row: usize },
+  // A tile of the path that enemies walking from the spawn never reach
+  Disconnected { path: usize, column: usize, row: usize },
+}
+
+impl fmt::Display for MapError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      MapError::RowCount { expected, found } => {
+        write!(f, "map has {} rows but a height of {}", found, expected)
+      }
+      MapError::RowLength { row, expected, found } => {
+        write!(f, "row {} has {} tiles but the map is {} wide", row, found, expected)
+      }
+      MapError::NoPaths => write!(f, "map has no path tiles"),
+      MapError::EmptyPath { path } => write!(f, "path {} has no tiles", path),
+      MapError::SpawnCount { path, found } => {
+        write!(f, "path {} touches {} spawns instead of one", path, found)
+      }
+      MapError::EndCount { path, found } => {
+        write!(f, "path {} touches {} ends instead of one", path, found)
+      }
+      MapError::Branch { path, column, row } => {
+        write!(f, "path {} branches at column {}, row {}", path, column, row)
+      }
+      MapError::DeadEnd { path, column, row } => write!(
+        f,
+        "path {} stops at column {}, row {} without reaching its end",
+        path, column, row
+      ),
+      MapError::Disconnected { path, column, row } => write!(
+        f,
+        "path {} isn't connected to the tile at column {}, row {}",
+        path, column, row
+      ),
+    }
+  }
+}
+
+impl Error for MapError {}
+
+impl Map {
+  // Check that the map can be played, before it is prepared. Every path needs exactly one
+  // spawn and one end next to its tiles, and a single line of tiles leading from one to the other
+  pub fn validate(&self) -> Result<(), Vec<MapError>> {
+    let mut errors = vec![];
+
+    if self.tiles.len() != self.height {
+      errors.push(MapError::RowCount {
+        expected: self.height,
+        found: self.tiles.len(),
+      });
+    }
+    for (row, tiles) in self.tiles.iter().enumerate() {
+      if tiles.len() != self.width {
+        errors.push(MapError::RowLength {
+          row,
+          expected: self.width,
+          found: tiles.len(),
+        });
+      }
+    }
+    // Paths can't be followed through a map with missing tiles
+    if !errors.is_empty() {
+      return Err(errors);
+    }
+
+    let mut path_tiles: Vec<Vec<Point>> = vec![];
+    let mut spawns = vec![];
+    let mut ends = vec![];
+    for (y, row) in self.tiles.iter().enumerate() {
+      for (x, tile) in row.iter().enumerate() {
+        match tile {
+          Tile::Spawn => spawns.push(Point { x, y }),
+          Tile::Path(ids) => {
+            for id in ids {
+              if path_tiles.len() <= *id {
+                path_tiles.resize(*id + 1, vec![]);
+              }
+              path_tiles[*id].push(Point { x, y });
+            }
+          }
+          Tile::End => ends.push(Point { x, y }),
+          _ => {}
+        }
+      }
+    }
+
+    if path_tiles.is_empty() {
+      errors.push(MapError::NoPaths);
+    }
+    for (path, tiles) in path_tiles.iter().enumerate() {
+      if let Err(error) = validate_path(path, tiles, &spawns, &ends) {
+        errors.push(error);
+      }
+    }
+
+    if errors.is_empty() {
+      Ok(())
+    } else {
+      Err(errors)
+    }
+  }
+}
+
+// Walk the path from its spawn like `Map::create_checkpoints` does. Points are in file order
+// here, the map hasn't been flipped yet
+fn validate_path(
+  path: usize,
+  tiles: &[Point],
+  spawns: &[Point],
+  ends: &[Point],
+) -> Result<(), MapError> {
+  if tiles.is_empty() {
+    return Err(MapError::EmptyPath { path });
+  }
+  let touching = |points: &[Point]| {
+    points
+      .iter()
+      .copied()
+      .filter(|point| tiles.iter().any(|tile| point.is_adjacent_to(*tile)))
+      .collect::<Vec<_>>()
+  };
+  let (spawn, end) = match (touching(spawns).as_slice(), touching(ends).as_slice()) {
+    ([spawn], [end]) => (*spawn, *end),
+    ([_], found) => return Err(MapError::EndCount { path, found: found.len() }),
+    (found, _) => return Err(MapError::SpawnCount { path, found: found.len() }),
+  };
+
+  let (walked, branch) = walk_path(spawn, tiles);
+  if let Some(point) = branch {
+    return Err(MapError::Branch {
+      path,
+      column: point.x,
+      row: point.y,
+    });
+  }
+
+  let last_point = walked.last().copied().unwrap_or(spawn);
+  if !last_point.is_adjacent_to(end) {
+    return Err(MapError::DeadEnd {
+      path,
+      column: last_point.x,
+      row: last_point.y,
+    });
+  }
+  // Tiles the walk never reached
+  if let Some(point) = tiles.iter().find(|tile| !walked.contains(tile)) {
+    return Err(MapError::Disconnected {
+      path,
+      column: point.x,
+      row: point.y,
+    });
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+#[path = "map/validation_tests.rs"]
+mod tests;
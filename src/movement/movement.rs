@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::{Bullet, GameState};
+use crate::{Bullet, GameplaySet};
 
 pub struct MovementPlugin;
 
@@ -9,7 +9,11 @@ impl Plugin for MovementPlugin {
   fn build(&self, app: &mut App) {
     app
       .register_type::<Movement>()
-      .add_system(basic_movement.in_set(OnUpdate(GameState::Gameplay)));
+      .add_system(
+        basic_movement
+          .in_set(GameplaySet::Simulation)
+          .in_schedule(CoreSchedule::FixedUpdate),
+      );
   }
 }
 
@@ -33,10 +37,11 @@ impl Movement {
 
 fn basic_movement(
   mut entities: Query<(&mut Movement, &mut Transform), With<Bullet>>,
-  time: Res<Time>,
+  fixed_time: Res<FixedTime>,
 ) {
   for (mut movement, mut transform) in &mut entities {
-    let distance = movement.direction.normalize() * movement.speed * time.delta_seconds();
+    let distance =
+      movement.direction.normalize() * movement.speed * fixed_time.period.as_secs_f32();
     movement.distance_travelled += distance.length();
     transform.translation += distance;
   }
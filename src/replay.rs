@@ -0,0 +1,3 @@
+#[allow(clippy::module_inception)]
+mod replay;
+pub use replay::*;
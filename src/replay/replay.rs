@@ -0,0 +1,221 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::Path as FilePath;
+
+use crate::assets::*;
+use crate::{
+  apply_player_commands, GameState, GameplaySet, PlayerCommand, SaveGame, SelectedLevel,
+  SimulationRng, SimulationStep, SimulationTick,
+};
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .add_state::<ReplayState>()
+      .init_resource::<ReplayRecording>()
+      // The viewer only watches, the player's input is ignored
+      .configure_set(GameplaySet::Input.run_if(in_state(ReplayState::Off)))
+      .add_system(start_replay_or_recording.in_schedule(OnEnter(GameState::Gameplay)))
+      .add_system(
+        play_replay
+          .before(apply_player_commands)
+          .in_set(SimulationStep::Commands)
+          .run_if(in_state(ReplayState::Playing))
+          .in_schedule(CoreSchedule::FixedUpdate),
+      )
+      .add_system(save_replay.in_schedule(OnEnter(GameState::GameOver)))
+      .add_system(save_replay.in_schedule(OnEnter(GameState::Victory)))
+      .add_system(stop_replay.in_schedule(OnEnter(GameState::MainMenu)));
+  }
+}
+
+pub const REPLAY_PATH: &str = "replay.ron";
+
+// Whether the level being played is a recorded run played back
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default, States)]
+pub enum ReplayState {
+  #[default]
+  Off,
+  Playing,
+}
+
+// Command issued after `tick` simulation steps, it takes effect before the next one
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TimedCommand {
+  pub tick: u64,
+  pub command: PlayerCommand,
+}
+
+// Everything needed to play a run again - the simulation is deterministic,
+// so the same commands on the same level with the same seed play out the same way
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Replay {
+  pub level: String,
+  pub seed: u64,
+  #[serde(default)]
+  pub endless: bool,
+  pub commands: Vec<TimedCommand>,
+}
+
+impl Replay {
+  pub fn new(level: &str, seed: u64) -> Self {
+    Self {
+      level: level.to_string(),
+      seed,
+      endless: false,
+      commands: vec![],
+    }
+  }
+
+  pub fn exists() -> bool {
+    FilePath::new(REPLAY_PATH).exists()
+  }
+
+  pub fn to_ron(&self) -> Result<String, Box<dyn Error>> {
+    Ok(ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?)
+  }
+
+  pub fn from_ron(ron: &str) -> Result<Self, Box<dyn Error>> {
+    Ok(ron::from_str(ron)?)
+  }
+
+  pub fn save(&self) -> Result<(), Box<dyn Error>> {
+    fs::write(REPLAY_PATH, self.to_ron()?)?;
+    Ok(())
+  }
+
+  pub fn load() -> Result<Self, Box<dyn Error>> {
+    Self::from_ron(&fs::read_to_string(REPLAY_PATH)?)
+  }
+}
+
+// Run being recorded. Runs continued from a save aren't, they don't start from the beginning
+#[derive(Resource, Default)]
+pub struct ReplayRecording {
+  pub replay: Option<Replay>,
+}
+
+// Replay being watched and the first of its commands that hasn't been played yet
+#[derive(Resource)]
+pub struct ReplayPlayback {
+  pub replay: Replay,
+  pub next: usize,
+}
+
+impl ReplayPlayback {
+  pub fn new(replay: Replay) -> Self {
+    Self { replay, next: 0 }
+  }
+
+  // Commands due once `tick` steps have run, each one is only returned once
+  pub fn due(&mut self, tick: u64) -> &[TimedCommand] {
+    let start = self.next;
+    let commands = &self.replay.commands[start..];
+    self.next += commands.iter().take_while(|command| command.tick <= tick).count();
+    &self.replay.commands[start..self.next]
+  }
+}
+
+// Records the commands applied to the game
+#[derive(SystemParam)]
+pub struct ReplayRecorder<'w> {
+  recording: ResMut<'w, ReplayRecording>,
+  tick: Res<'w, SimulationTick>,
+}
+
+impl ReplayRecorder<'_> {
+  pub fn record(&mut self, command: PlayerCommand) {
+    let tick = self.tick.0;
+    if let Some(replay) = &mut self.recording.replay {
+      replay.commands.push(TimedCommand { tick, command });
+    }
+  }
+}
+
+#[derive(Component)]
+pub struct ReplayUI;
+
+fn start_replay_or_recording(
+  mut commands: Commands,
+  mut recording: ResMut<ReplayRecording>,
+  mut rng: ResMut<SimulationRng>,
+  playback: Option<ResMut<ReplayPlayback>>,
+  save_game: Option<Res<SaveGame>>,
+  selected_level: Res<SelectedLevel>,
+  assets: Res<GameAssets>,
+) {
+  let Some(mut playback) = playback else {
+    recording.replay = save_game
+      .is_none()
+      .then(|| Replay {
+        endless: selected_level.endless,
+        ..Replay::new(&selected_level.name, rng.seed)
+      });
+    return;
+  };
+
+  recording.replay = None;
+  playback.next = 0;
+  *rng = SimulationRng::new(playback.replay.seed);
+  spawn_replay_ui(&mut commands, &assets);
+}
+
+// Send the recorded commands as if the player had just issued them
+fn play_replay(
+  mut playback: ResMut<ReplayPlayback>,
+  tick: Res<SimulationTick>,
+  mut player_commands: EventWriter<PlayerCommand>,
+) {
+  for timed in playback.due(tick.0) {
+    player_commands.send(timed.command.clone());
+  }
+}
+
+fn save_replay(recording: Res<ReplayRecording>) {
+  let Some(replay) = &recording.replay
+    else { return; };
+
+  match replay.save() {
+    Ok(()) => info!("Replay saved to {}", REPLAY_PATH),
+    Err(error) => error!("Failed to save replay: {}", error),
+  }
+}
+
+fn stop_replay(mut commands: Commands, mut replay_state: ResMut<NextState<ReplayState>>) {
+  commands.remove_resource::<ReplayPlayback>();
+  replay_state.set(ReplayState::Off);
+}
+
+fn spawn_replay_ui(commands: &mut Commands, assets: &GameAssets) {
+  commands
+    .spawn(
+      TextBundle::from_section(
+        "Replay",
+        TextStyle {
+          font: assets.font.clone(),
+          font_size: 40.,
+          color: Color::CYAN,
+        },
+      )
+      .with_style(Style {
+        position_type: PositionType::Absolute,
+        position: UiRect {
+          top: Val::Px(10.),
+          left: Val::Percent(45.),
+          ..default()
+        },
+        ..default()
+      }),
+    )
+    .insert(ReplayUI)
+    .insert(Name::new("ReplayUI"));
+}
+
+#[cfg(test)]
+#[path = "replay/replay_tests.rs"]
+mod tests;
@@ -0,0 +1,168 @@
+use super::*;
+use crate::gameplay_ui::*;
+use crate::tower::*;
+
+fn timed(tick: u64, command: PlayerCommand) -> TimedCommand {
+    TimedCommand { tick, command }
+}
+
+fn example_replay() -> Replay {
+    let tower = Vec3::new(320.5, 80.25, 0.5);
+    Replay {
+        level: "level1".to_string(),
+        seed: 7,
+        endless: true,
+        commands: vec![
+            timed(
+                0,
+                PlayerCommand::PlaceTower {
+                    tower_type: TowerType::Fire,
+                    position: tower,
+                },
+            ),
+            timed(0, PlayerCommand::CallWave),
+            timed(
+                120,
+                PlayerCommand::SetTargeting {
+                    tower,
+                    target: TargetingPriority::RANDOM,
+                },
+            ),
+            timed(300, PlayerCommand::Upgrade { tower, path_index: 2 }),
+            timed(900, PlayerCommand::Sell { tower }),
+        ],
+    }
+}
+
+#[test]
+fn test_replay_ron_round_trip() {
+    let replay = example_replay();
+    let ron = replay.to_ron().unwrap();
+    assert_eq!(Replay::from_ron(&ron).unwrap(), replay);
+}
+
+#[test]
+fn test_replay_positions_are_exact() {
+    // Towers are found again by comparing positions, so they can't lose precision
+    let position = Vec3::new(0.1, 1. / 3., 123.456_79);
+    let mut replay = Replay::new("level1", 0);
+    replay.commands.push(timed(0, PlayerCommand::Sell { tower: position }));
+
+    let loaded = Replay::from_ron(&replay.to_ron().unwrap()).unwrap();
+    assert_eq!(loaded.commands[0].command, PlayerCommand::Sell { tower: position });
+}
+
+#[test]
+fn test_playback_returns_commands_once_they_are_due() {
+    let mut playback = ReplayPlayback::new(example_replay());
+
+    assert_eq!(playback.due(0).len(), 2);
+    assert!(playback.due(0).is_empty());
+    assert!(playback.due(119).is_empty());
+    assert_eq!(playback.due(120)[0].tick, 120);
+}
+
+#[test]
+fn test_playback_catches_up_on_skipped_ticks() {
+    let mut playback = ReplayPlayback::new(example_replay());
+
+    assert_eq!(playback.due(1000).len(), 5);
+    assert!(playback.due(2000).is_empty());
+}
+
+#[test]
+fn test_new_replay_is_empty() {
+    let replay = Replay::new("level2", 3);
+    assert_eq!(replay.level, "level2");
+    assert_eq!(replay.seed, 3);
+    assert!(replay.commands.is_empty());
+}
+
+// Watch a replay of level 1 for `steps` simulation steps, `steps_per_frame` of them every frame
+fn watch(replay: &Replay, steps: u32, steps_per_frame: u32) -> App {
+    use bevy::time::TimeUpdateStrategy;
+    use crate::{headless_app, Scenario, SIMULATION_TIMESTEP};
+
+    let scenario = Scenario::from_ron(
+        r#"(map: "assets/data/level1.map.ron", waves: "assets/data/enemy.waves.ron")"#,
+    )
+    .unwrap();
+
+    let mut app = headless_app(&scenario).unwrap();
+    app.add_plugin(ReplayPlugin)
+        .insert_resource(ReplayPlayback::new(replay.clone()));
+    app.world
+        .resource_mut::<NextState<ReplayState>>()
+        .set(ReplayState::Playing);
+
+    let start = app.world.resource::<Time>().startup();
+    let frame_time = SIMULATION_TIMESTEP * steps_per_frame;
+    for frame in 0..=steps / steps_per_frame {
+        app.insert_resource(TimeUpdateStrategy::ManualInstant(start + frame_time * frame));
+        app.update();
+    }
+    app
+}
+
+// Money, and damage and targeting of every tower
+fn outcome(app: &mut App) -> (usize, Vec<(u32, TargetingPriority)>) {
+    let money = app.world.query::<&Player>().single(&app.world).money;
+    let towers = app
+        .world
+        .query::<&Tower>()
+        .iter(&app.world)
+        .map(|tower| (tower.total_damage, tower.target.clone()))
+        .collect();
+    (money, towers)
+}
+
+fn level1_replay() -> Replay {
+    let tower = Vec3::new(320., 80., 0.);
+    let mut replay = Replay::new("level1", 5);
+    replay.commands = vec![
+        timed(
+            0,
+            PlayerCommand::PlaceTower {
+                tower_type: TowerType::Fire,
+                position: tower,
+            },
+        ),
+        timed(
+            30,
+            PlayerCommand::SetTargeting {
+                tower,
+                target: TargetingPriority::RANDOM,
+            },
+        ),
+        timed(1200, PlayerCommand::Upgrade { tower, path_index: 0 }),
+    ];
+    replay
+}
+
+#[test]
+fn test_replay_plays_commands() {
+    let tower_stats: TowerTypeStats =
+        ron::from_str(include_str!("../../../assets/data/stats.tower_stats.ron")).unwrap();
+    let upgrades: Upgrades =
+        ron::from_str(include_str!("../../../assets/data/tower.upgrades.ron")).unwrap();
+    let price = tower_stats.tower[&TowerType::Fire].tower.price as usize;
+    let upgrade_cost = upgrades.upgrades[&TowerType::Fire][0][0].cost;
+
+    let mut app = watch(&level1_replay(), 1800, 1);
+    let (money, towers) = outcome(&mut app);
+    let money_earned = app.world.query::<&Player>().single(&app.world).money_earned;
+
+    assert_eq!(towers.len(), 1);
+    assert!(towers[0].0 > 0);
+    assert_eq!(towers[0].1, TargetingPriority::RANDOM);
+    assert_eq!(app.world.resource::<ReplayPlayback>().next, 3);
+    assert_eq!(money + price + upgrade_cost, 100 + money_earned);
+}
+
+#[test]
+fn test_replay_does_not_depend_on_frame_rate() {
+    let replay = level1_replay();
+    let mut every_step = watch(&replay, 3600, 1);
+    let mut every_fourth_step = watch(&replay, 3600, 4);
+    assert_eq!(outcome(&mut every_step), outcome(&mut every_fourth_step));
+}
@@ -0,0 +1,3 @@
+#[allow(clippy::module_inception)]
+mod save;
+pub use save::*;
@@ -0,0 +1,262 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::Path as FilePath;
+use std::time::Duration;
+
+use crate::assets::*;
+use crate::enemy::*;
+use crate::gameplay_ui::*;
+use crate::movement::*;
+use crate::tower::*;
+use crate::{GameData, GameState, Map, SelectedLevel, SimulationRng, SimulationStep};
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .add_system(save_button_clicked.in_set(OnUpdate(GameState::Gameplay)))
+      // Restore the run before the first step can spawn or move enemies
+      .add_system(
+        restore_saved_game
+          .run_if(resource_exists::<SaveGame>())
+          .in_set(SimulationStep::Prepare)
+          .in_schedule(CoreSchedule::FixedUpdate),
+      )
+      // A finished run can't be continued
+      .add_system(delete_save.in_schedule(OnEnter(GameState::GameOver)))
+      .add_system(delete_save.in_schedule(OnEnter(GameState::Victory)));
+  }
+}
+
+pub const SAVE_PATH: &str = "savegame.ron";
+
+#[derive(Component)]
+pub struct SaveButton;
+
+// Saves from before the timer was stored wait the full time for the next wave
+fn default_wave_spawn_timer() -> Timer {
+  Timer::new(TIME_BETWEEN_WAVES, TimerMode::Once)
+}
+
+// Saves from before the timer was stored spawn the next enemy right away
+fn default_enemy_spawn_timer() -> Timer {
+  Timer::new(Duration::from_millis(1), TimerMode::Repeating)
+}
+
+// Everything needed to continue a run where it was left off
+#[derive(Resource, Serialize, Deserialize, Clone)]
+pub struct SaveGame {
+  pub level: String,
+  #[serde(default)]
+  pub endless: bool,
+  // Endless waves are generated from the seed and the state picks up the random choices where
+  // the saved run left off, so a continued run plays out like the saved one would have
+  #[serde(default)]
+  pub rng: SimulationRng,
+  pub player: Player,
+  pub base: Base,
+  pub wave: usize,
+  // Enemies of the current wave that haven't been spawned yet
+  pub remaining: usize,
+  // Time until the next wave is sent, once the current one has been spawned
+  #[serde(default = "default_wave_spawn_timer")]
+  pub wave_spawn_timer: Timer,
+  // Time until the next enemy of the current wave is spawned
+  #[serde(default = "default_enemy_spawn_timer")]
+  pub enemy_spawn_timer: Timer,
+  pub towers: Vec<SavedTower>,
+  pub enemies: Vec<SavedEnemy>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SavedTower {
+  pub tower: TowerBundle,
+  pub position: Vec3,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SavedEnemy {
+  pub enemy: EnemyBundle,
+  pub position: Vec3,
+}
+
+impl SaveGame {
+  pub fn exists() -> bool {
+    FilePath::new(SAVE_PATH).exists()
+  }
+
+  pub fn to_ron(&self) -> Result<String, Box<dyn Error>> {
+    Ok(ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?)
+  }
+
+  pub fn from_ron(ron: &str) -> Result<Self, Box<dyn Error>> {
+    Ok(ron::from_str(ron)?)
+  }
+
+  pub fn save(&self) -> Result<(), Box<dyn Error>> {
+    fs::write(SAVE_PATH, self.to_ron()?)?;
+    Ok(())
+  }
+
+  pub fn load() -> Result<Self, Box<dyn Error>> {
+    Self::from_ron(&fs::read_to_string(SAVE_PATH)?)
+  }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn save_button_clicked(
+  interactions: Query<&Interaction, (With<SaveButton>, Changed<Interaction>)>,
+  selected_level: Res<SelectedLevel>,
+  game_data: Res<GameData>,
+  waves: Res<Assets<Waves>>,
+  wave_state: Res<WaveState>,
+  rng: Res<SimulationRng>,
+  player: Query<&Player>,
+  base: Query<&Base>,
+  towers: Query<(&TowerType, &Tower, &TowerLifetimeStats, &Name, &Transform)>,
+  enemies: Query<(
+    &EnemyType,
+    &Enemy,
+    &EnemyAbilities,
+    &StatusEffects,
+    &Movement,
+    &AnimationIndices,
+    &AnimationTimer,
+    &Path,
+    &Name,
+    &Transform,
+  )>,
+) {
+  if !interactions
+    .iter()
+    .any(|interaction| matches!(interaction, Interaction::Clicked))
+  {
+    return;
+  }
+  let Some(waves) = waves.get(&game_data.enemy_waves)
+    else { return; };
+
+  let save_game = SaveGame {
+    level: selected_level.name.clone(),
+    endless: selected_level.endless,
+    rng: rng.clone(),
+    player: player.single().clone(),
+    base: base.single().clone(),
+    wave: waves.current,
+    remaining: wave_state.remaining,
+    wave_spawn_timer: wave_state.wave_spawn_timer.clone(),
+    enemy_spawn_timer: wave_state.enemy_spawn_timer.clone(),
+    towers: towers
+      .iter()
+      .map(|(tower_type, tower, lifetime_stats, name, transform)| SavedTower {
+        tower: TowerBundle {
+          tower_type: *tower_type,
+          tower: tower.clone(),
+          lifetime_stats: lifetime_stats.clone(),
+          name: name.clone(),
+        },
+        position: transform.translation,
+      })
+      .collect(),
+    enemies: enemies
+      .iter()
+      .map(
+        |(
+          enemy_type,
+          enemy,
+          abilities,
+          status_effects,
+          movement,
+          animation_indices,
+          animation_timer,
+          path,
+          name,
+          transform,
+        )| {
+          SavedEnemy {
+            enemy: EnemyBundle {
+              enemy_type: *enemy_type,
+              enemy: enemy.clone(),
+              abilities: abilities.clone(),
+              status_effects: status_effects.clone(),
+              movement: movement.clone(),
+              animation_indices: animation_indices.clone(),
+              animation_timer: animation_timer.clone(),
+              path: path.clone(),
+              name: name.clone(),
+            },
+            position: transform.translation,
+          }
+        },
+      )
+      .collect(),
+  };
+
+  match save_game.save() {
+    Ok(()) => info!("Game saved to {}", SAVE_PATH),
+    Err(error) => error!("Failed to save game: {}", error),
+  }
+}
+
+// Replace the freshly started level with the saved run
+#[allow(clippy::too_many_arguments)]
+fn restore_saved_game(
+  mut commands: Commands,
+  save_game: Res<SaveGame>,
+  assets: Res<GameAssets>,
+  game_data: Res<GameData>,
+  mut waves: ResMut<Assets<Waves>>,
+  enemy_type_assets: Res<Assets<EnemyTypeStats>>,
+  map: Res<Map>,
+  mut rng: ResMut<SimulationRng>,
+  mut wave_state: ResMut<WaveState>,
+  mut player: Query<&mut Player>,
+  mut base: Query<&mut Base>,
+) {
+  commands.remove_resource::<SaveGame>();
+
+  *player.single_mut() = save_game.player.clone();
+  *base.single_mut() = save_game.base.clone();
+  *rng = save_game.rng.clone();
+
+  if let (Some(waves), Some(enemy_stats)) = (
+    waves.get_mut(&game_data.enemy_waves),
+    enemy_type_assets.get(&game_data.enemy_type_stats),
+  ) {
+    waves.current = save_game.wave;
+    waves.generate_endless_wave(rng.seed, enemy_stats);
+  }
+  wave_state.remaining = save_game.remaining;
+  wave_state.wave_spawn_timer = save_game.wave_spawn_timer.clone();
+  wave_state.enemy_spawn_timer = save_game.enemy_spawn_timer.clone();
+
+  // The towers were paid for in the saved run, the saved player's money already accounts for them
+  for saved in &save_game.towers {
+    spawn_tower(&mut commands, &saved.tower, &assets, saved.position);
+  }
+
+  for (index, saved) in save_game.enemies.iter().enumerate() {
+    let mut enemy = saved.enemy.clone();
+    // The map file could have lost paths since the run was saved. Enemies on a path it doesn't
+    // have take one of its paths instead, like the enemies of a wave
+    enemy.path.id = choose_path(Some(enemy.path.id), index, map.path_count());
+    commands
+      .spawn(enemy)
+      .insert(saved.enemy.enemy_type.get_sprite_sheet_bundle(&assets, saved.position));
+  }
+}
+
+fn delete_save() {
+  if SaveGame::exists() {
+    if let Err(error) = fs::remove_file(SAVE_PATH) {
+      error!("Failed to delete save: {}", error);
+    }
+  }
+}
+
+#[cfg(test)]
+#[path = "save/save_tests.rs"]
+mod tests;
@@ -0,0 +1,125 @@
+use super::*;
+
+fn save_game() -> SaveGame {
+    let mut player = Player::new(250);
+    player.earn(40);
+
+    let mut tower = Tower::new(Vec3::new(0., 20., 0.), 5, 0.8, 150, 100);
+    tower.upgrades.upgrades = vec![2, 0, 1];
+    tower.target = TargetingPriority::STRONG;
+    tower.total_damage = 320;
+
+    let mut enemy = EnemyBundle {
+        path: Path { id: 1, index: 3 },
+        ..default()
+    };
+    enemy.movement.distance_travelled = 412.5;
+
+    SaveGame {
+        level: "level1".to_string(),
+        endless: true,
+        rng: SimulationRng::new(42),
+        player,
+        base: Base { health: 65 },
+        wave: 4,
+        remaining: 2,
+        wave_spawn_timer: Timer::new(Duration::from_secs(4), TimerMode::Once),
+        enemy_spawn_timer: Timer::new(Duration::from_secs(2), TimerMode::Repeating),
+        towers: vec![SavedTower {
+            tower: TowerBundle {
+                tower_type: TowerType::Ice,
+                tower,
+                lifetime_stats: TowerLifetimeStats {
+                    kills: 12,
+                    shots_fired: 40,
+                    shots_hit: 30,
+                    ..default()
+                },
+                name: Name::new("IceWizard"),
+            },
+            position: Vec3::new(128., -64., 0.),
+        }],
+        enemies: vec![SavedEnemy {
+            enemy,
+            position: Vec3::new(300., 40., 1.),
+        }],
+    }
+}
+
+#[test]
+fn test_save_game_round_trip() {
+    let ron = save_game().to_ron().unwrap();
+    let loaded = SaveGame::from_ron(&ron).unwrap();
+
+    assert_eq!(loaded.level, "level1");
+    assert_eq!(loaded.player.money, 290);
+    assert_eq!(loaded.player.money_earned, 40);
+    assert_eq!(loaded.base.health, 65);
+    assert_eq!(loaded.wave, 4);
+    assert_eq!(loaded.remaining, 2);
+    assert!(loaded.endless);
+    assert_eq!(loaded.rng.seed, 42);
+}
+
+#[test]
+fn test_save_game_keeps_time_until_next_wave() {
+    let mut save_game = save_game();
+    save_game.wave_spawn_timer.tick(Duration::from_secs(1));
+    let loaded = SaveGame::from_ron(&save_game.to_ron().unwrap()).unwrap();
+    assert_eq!(loaded.wave_spawn_timer.remaining(), Duration::from_secs(3));
+}
+
+#[test]
+fn test_save_game_keeps_time_until_next_enemy() {
+    let mut save_game = save_game();
+    save_game.enemy_spawn_timer.tick(Duration::from_millis(500));
+    let loaded = SaveGame::from_ron(&save_game.to_ron().unwrap()).unwrap();
+    assert_eq!(loaded.enemy_spawn_timer.remaining(), Duration::from_millis(1500));
+    assert_eq!(loaded.enemy_spawn_timer.mode(), TimerMode::Repeating);
+}
+
+#[test]
+fn test_save_game_continues_random_choices() {
+    let mut save_game = save_game();
+    save_game.rng.choose(0..1000);
+    let mut loaded = SaveGame::from_ron(&save_game.to_ron().unwrap()).unwrap();
+
+    let expected = (0..10).map(|_| save_game.rng.choose(0..1000)).collect::<Vec<_>>();
+    let continued = (0..10).map(|_| loaded.rng.choose(0..1000)).collect::<Vec<_>>();
+    assert_eq!(continued, expected);
+}
+
+#[test]
+fn test_save_game_keeps_towers() {
+    let ron = save_game().to_ron().unwrap();
+    let loaded = SaveGame::from_ron(&ron).unwrap();
+
+    let saved = &loaded.towers[0];
+    assert_eq!(saved.tower.tower_type, TowerType::Ice);
+    assert_eq!(saved.position, Vec3::new(128., -64., 0.));
+    assert_eq!(saved.tower.tower.damage, 5);
+    assert_eq!(saved.tower.tower.range, 150);
+    assert_eq!(saved.tower.tower.total_damage, 320);
+    assert_eq!(saved.tower.tower.upgrades.upgrades, vec![2, 0, 1]);
+    assert_eq!(saved.tower.tower.target, TargetingPriority::STRONG);
+    assert_eq!(saved.tower.lifetime_stats.kills, 12);
+    assert_eq!(saved.tower.lifetime_stats.accuracy(), 75.);
+}
+
+#[test]
+fn test_save_game_keeps_enemy_progress() {
+    let ron = save_game().to_ron().unwrap();
+    let loaded = SaveGame::from_ron(&ron).unwrap();
+
+    let saved = &loaded.enemies[0];
+    assert_eq!(saved.enemy.enemy_type, EnemyType::Green);
+    assert_eq!(saved.enemy.path.id, 1);
+    assert_eq!(saved.enemy.path.index, 3);
+    assert_eq!(saved.enemy.movement.distance_travelled, 412.5);
+    assert_eq!(saved.position, Vec3::new(300., 40., 1.));
+}
+
+#[test]
+fn test_save_game_rejects_invalid_ron() {
+    assert!(SaveGame::from_ron("not a save").is_err());
+}
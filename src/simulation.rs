@@ -0,0 +1,6 @@
+mod headless;
+pub use headless::*;
+mod rng;
+pub use rng::*;
+mod player_command;
+pub use player_command::*;
@@ -0,0 +1,381 @@
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fs;
+
+use crate::enemy::*;
+use crate::gameplay_ui::*;
+use crate::map::*;
+use crate::movement::*;
+use crate::tower::*;
+use crate::{
+  GameAssets, GameData, GameState, GameStatePlugin, GameplaySet, PlayerCommand,
+  PlayerCommandPlugin, ReplayRecording, SelectedLevel, SimulationRng, SIMULATION_TIMESTEP,
+};
+
+fn default_enemy_stats() -> String {
+  "assets/data/stats.enemy_types.ron".to_string()
+}
+
+fn default_tower_stats() -> String {
+  "assets/data/stats.tower_stats.ron".to_string()
+}
+
+fn default_tower_upgrades() -> String {
+  "assets/data/tower.upgrades.ron".to_string()
+}
+
+fn default_time_limit() -> f32 {
+  3600.
+}
+
+// Game to play without a window, read from a RON file. Paths are relative to the working directory
+#[derive(Deserialize, Clone, Debug)]
+pub struct Scenario {
+  pub map: String,
+  pub waves: String,
+  #[serde(default = "default_enemy_stats")]
+  pub enemy_stats: String,
+  #[serde(default = "default_tower_stats")]
+  pub tower_stats: String,
+  #[serde(default = "default_tower_upgrades")]
+  pub tower_upgrades: String,
+  #[serde(default)]
+  pub towers: Vec<ScriptedTower>,
+  // Seconds of game time after which the simulation gives up
+  #[serde(default = "default_time_limit")]
+  pub time_limit: f32,
+  // Seed of the random choices made during the game, like towers targeting a random enemy
+  #[serde(default)]
+  pub seed: u64,
+  // Keep generating waves after the scripted ones, the game only ends by losing or the time limit
+  #[serde(default)]
+  pub endless: bool,
+}
+
+// Tower placed as soon as its wave has started and the player can afford it.
+// Columns and rows are counted from the top left, like the tiles in the map files
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ScriptedTower {
+  pub tower_type: TowerType,
+  pub column: usize,
+  pub row: usize,
+  #[serde(default)]
+  pub wave: usize,
+}
+
+// Towers still waiting to be placed, in order
+#[derive(Resource, Default)]
+pub struct TowerScript {
+  pub pending: VecDeque<ScriptedTower>,
+  pub placed: Vec<ScriptedTower>,
+  // Towers whose tile is taken or doesn't allow them
+  pub rejected: Vec<ScriptedTower>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum SimulationOutcome {
+  Victory,
+  GameOver,
+  TimeLimit,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SimulationReport {
+  pub outcome: SimulationOutcome,
+  pub waves_survived: usize,
+  // Endless round the run got to, counted from 1. None if it didn't get past the scripted waves
+  pub endless_round: Option<usize>,
+  // Seconds of game time simulated
+  pub elapsed: f32,
+  pub base_health: i32,
+  pub money: usize,
+  pub money_earned: usize,
+  pub towers: Vec<TowerReport>,
+  // Towers that were never placed, because of their tile or a lack of money
+  pub unplaced_towers: Vec<ScriptedTower>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TowerReport {
+  #[serde(flatten)]
+  pub placement: ScriptedTower,
+  pub total_damage: u32,
+  pub kills: u32,
+  pub shots_fired: u32,
+  pub shots_hit: u32,
+  pub damage_by_enemy_type: Vec<(EnemyType, u32)>,
+}
+
+impl Scenario {
+  pub fn from_ron(ron: &str) -> Result<Self, Box<dyn Error>> {
+    Ok(ron::from_str(ron)?)
+  }
+
+  pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+    Self::from_ron(&fs::read_to_string(path)?)
+  }
+}
+
+impl ScriptedTower {
+  // World position of the tower's tile. Map rows are flipped when the map is prepared
+  pub fn position(&self, map: &Map) -> Vec3 {
+    let row = map.height.saturating_sub(self.row + 1);
+    Vec3::new(
+      (self.column * map.tile_size) as f32,
+      (row * map.tile_size) as f32,
+      0.,
+    )
+  }
+}
+
+impl SimulationReport {
+  pub fn to_json(&self) -> Result<String, Box<dyn Error>> {
+    Ok(serde_json::to_string_pretty(self)?)
+  }
+}
+
+fn read_ron<T: for<'de> Deserialize<'de>>(path: &str) -> Result<T, Box<dyn Error>> {
+  let ron = fs::read_to_string(path).map_err(|error| format!("{}: {}", path, error))?;
+  Ok(ron::from_str(&ron).map_err(|error| format!("{}: {}", path, error))?)
+}
+
+// Gameplay plugins without windows, rendering, UI or asset loading
+pub fn headless_app(scenario: &Scenario) -> Result<App, Box<dyn Error>> {
+  let map: Map = read_ron(&scenario.map)?;
+  if let Err(errors) = map.validate() {
+    let errors = errors.iter().map(|error| error.to_string()).collect::<Vec<_>>();
+    return Err(format!("{}: {}", scenario.map, errors.join(", ")).into());
+  }
+  let waves: Waves = read_ron(&scenario.waves)?;
+  let enemy_stats: EnemyTypeStats = read_ron(&scenario.enemy_stats)?;
+  if let Err(errors) = waves.validate(&enemy_stats) {
+    let errors = errors.iter().map(|error| error.to_string()).collect::<Vec<_>>();
+    return Err(format!("{}: {}", scenario.waves, errors.join(", ")).into());
+  }
+  let tower_stats: TowerTypeStats = read_ron(&scenario.tower_stats)?;
+  let tower_upgrades: Upgrades = read_ron(&scenario.tower_upgrades)?;
+
+  let mut app = App::new();
+  app
+    .add_plugins(MinimalPlugins)
+    .add_plugin(bevy::asset::AssetPlugin::default())
+    .add_plugin(TransformPlugin)
+    .add_plugin(HierarchyPlugin)
+    .add_plugin(InputPlugin)
+    .add_plugin(GameStatePlugin)
+    .add_asset::<Map>()
+    .add_asset::<Waves>()
+    .add_asset::<EnemyTypeStats>()
+    .add_asset::<TowerTypeStats>()
+    .add_asset::<Upgrades>()
+    // Tower ranges are drawn as meshes
+    .add_asset::<Mesh>()
+    .add_asset::<ColorMaterial>()
+    .init_resource::<GameSpeed>()
+    // Scenarios aren't recorded
+    .init_resource::<ReplayRecording>()
+    .insert_resource(GameAssets::default())
+    .insert_resource(SimulationRng::new(scenario.seed))
+    .insert_resource(TowerScript {
+      pending: scenario.towers.iter().cloned().collect(),
+      ..default()
+    })
+    .add_plugin(PlayerCommandPlugin)
+    .add_plugin(PlayerPlugin)
+    .add_plugin(BasePlugin)
+    .add_plugin(MapPlugin)
+    .add_plugin(TowerPlugin)
+    .add_plugin(TowerLifetimeStatsPlugin)
+    .add_plugin(EnemyPlugin)
+    .add_plugin(EnemyAbilityPlugin)
+    .add_plugin(WavePlugin)
+    .add_plugin(BulletPlugin)
+    .add_plugin(MovementPlugin)
+    // Decided every step rather than every frame, like the rest of the simulation
+    .add_system(
+      place_scripted_towers
+        .in_set(GameplaySet::Simulation)
+        .in_schedule(CoreSchedule::FixedUpdate),
+    );
+
+  let game_data = GameData {
+    enemy_type_stats: app.world.resource_mut::<Assets<EnemyTypeStats>>().add(enemy_stats),
+    levels: vec![],
+    tower_type_stats: app.world.resource_mut::<Assets<TowerTypeStats>>().add(tower_stats),
+    tower_upgrades: app.world.resource_mut::<Assets<Upgrades>>().add(tower_upgrades),
+    enemy_waves: app.world.resource_mut::<Assets<Waves>>().add(waves),
+  };
+  let selected_level = SelectedLevel {
+    name: scenario.map.clone(),
+    map: app.world.resource_mut::<Assets<Map>>().add(map),
+    endless: scenario.endless,
+  };
+  app.insert_resource(game_data).insert_resource(selected_level);
+  app
+    .world
+    .resource_mut::<NextState<GameState>>()
+    .set(GameState::Gameplay);
+
+  Ok(app)
+}
+
+// Play the scenario one simulation step per frame until the game ends or runs out of time
+pub fn run_headless(scenario: &Scenario) -> Result<SimulationReport, Box<dyn Error>> {
+  let mut app = headless_app(scenario)?;
+
+  let start = app.world.resource::<Time>().startup();
+  let frames = (scenario.time_limit / SIMULATION_TIMESTEP.as_secs_f32()).ceil() as u32;
+  let mut outcome = SimulationOutcome::TimeLimit;
+
+  // The first frame only starts the clock
+  for frame in 0..=frames {
+    app.insert_resource(TimeUpdateStrategy::ManualInstant(start + SIMULATION_TIMESTEP * frame));
+    app.update();
+
+    match app.world.resource::<State<GameState>>().0 {
+      GameState::Victory => outcome = SimulationOutcome::Victory,
+      GameState::GameOver => outcome = SimulationOutcome::GameOver,
+      _ => continue,
+    }
+    break;
+  }
+
+  Ok(simulation_report(&mut app.world, outcome))
+}
+
+// Entry point for `--headless <scenario.ron>`, prints the report as JSON
+pub fn run_headless_cli(path: &str) {
+  let report = Scenario::load(path)
+    .and_then(|scenario| run_headless(&scenario))
+    .and_then(|report| report.to_json());
+
+  match report {
+    Ok(json) => println!("{}", json),
+    Err(error) => {
+      eprintln!("Headless simulation failed: {}", error);
+      std::process::exit(1);
+    }
+  }
+}
+
+fn simulation_report(world: &mut World, outcome: SimulationOutcome) -> SimulationReport {
+  // Towers are placed through player commands, so they are found again by their position
+  let placed = world.resource::<TowerScript>().placed.clone();
+  let positions = placed
+    .iter()
+    .map(|placement| placement.position(world.resource::<Map>()))
+    .collect::<Vec<_>>();
+  let mut towers = world
+    .query::<(&Tower, &TowerLifetimeStats, &Transform)>()
+    .iter(world)
+    .filter_map(|(tower, stats, transform)| {
+      let index = positions
+        .iter()
+        .position(|position| *position == transform.translation)?;
+      Some(TowerReport {
+        placement: placed[index].clone(),
+        total_damage: tower.total_damage,
+        kills: stats.kills,
+        shots_fired: stats.shots_fired,
+        shots_hit: stats.shots_hit,
+        damage_by_enemy_type: stats.damage_breakdown(),
+      })
+    })
+    .collect::<Vec<_>>();
+  towers.sort_by_key(|report| (report.placement.row, report.placement.column));
+
+  let base_health = world
+    .query::<&Base>()
+    .get_single(world)
+    .map_or(0, |base| base.health);
+  let (money, money_earned) = world
+    .query::<&Player>()
+    .get_single(world)
+    .map_or((0, 0), |player| (player.money, player.money_earned));
+
+  let game_data = world.resource::<GameData>();
+  let waves = world.resource::<Assets<Waves>>().get(&game_data.enemy_waves);
+  let waves_survived = waves.map_or(0, |waves| match outcome {
+    SimulationOutcome::Victory => waves.waves.len(),
+    _ => waves.current,
+  });
+  let endless_round = waves.and_then(|waves| waves.endless_round());
+
+  let script = world.resource::<TowerScript>();
+  let unplaced_towers = script
+    .rejected
+    .iter()
+    .chain(script.pending.iter())
+    .cloned()
+    .collect();
+
+  SimulationReport {
+    outcome,
+    waves_survived,
+    endless_round,
+    elapsed: world.resource::<Time>().elapsed_seconds(),
+    base_health,
+    money,
+    money_earned,
+    towers,
+    unplaced_towers,
+  }
+}
+
+// Placed with the same commands as the player's clicks, which are carried out at the start of
+// the next step
+fn place_scripted_towers(
+  mut script: ResMut<TowerScript>,
+  map: Res<Map>,
+  game_data: Res<GameData>,
+  waves: Res<Assets<Waves>>,
+  tower_type_assets: Res<Assets<TowerTypeStats>>,
+  player: Query<&Player>,
+  mut player_commands: EventWriter<PlayerCommand>,
+) {
+  let (Some(waves), Some(tower_stats)) = (
+    waves.get(&game_data.enemy_waves),
+    tower_type_assets.get(&game_data.tower_type_stats),
+  )
+    else { return; };
+  let mut money = player.single().money;
+
+  while let Some(next) = script.pending.front() {
+    if next.wave > waves.current {
+      return;
+    }
+
+    let position = next.position(&map);
+    let taken = script
+      .placed
+      .iter()
+      .any(|tower| tower.column == next.column && tower.row == next.row);
+    if taken || !tile_allows_tower(&map, next.tower_type, position) {
+      let rejected = script.pending.pop_front().unwrap();
+      script.rejected.push(rejected);
+      continue;
+    }
+
+    // Later towers wait for this one, like a player saving up for it
+    let price = tower_stats.tower[&next.tower_type].tower.price as usize;
+    if money < price {
+      return;
+    }
+    money -= price;
+
+    let next = script.pending.pop_front().unwrap();
+    player_commands.send(PlayerCommand::PlaceTower {
+      tower_type: next.tower_type,
+      position,
+    });
+    script.placed.push(next);
+  }
+}
+
+#[cfg(test)]
+#[path = "simulation/headless_tests.rs"]
+mod tests;
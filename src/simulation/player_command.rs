@@ -0,0 +1,148 @@
+use bevy::prelude::*;
+use bevy::sprite::Mesh2dHandle;
+use serde::{Deserialize, Serialize};
+
+use crate::assets::*;
+use crate::gameplay_ui::*;
+use crate::tower::*;
+use crate::{
+  CallWaveEvent, GameData, Map, ReplayRecorder, SimulationEvents, SimulationStep,
+};
+
+pub struct PlayerCommandPlugin;
+
+impl Plugin for PlayerCommandPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .add_simulation_event::<PlayerCommand>()
+      // Towers placed by a command have to exist before the rest of the step sees them
+      .add_systems(
+        (apply_player_commands, apply_system_buffers)
+          .chain()
+          .in_set(SimulationStep::Commands)
+          .in_schedule(CoreSchedule::FixedUpdate),
+      );
+  }
+}
+
+// Everything the player can do that changes the game. Keyboard, mouse and buttons,
+// replays and headless scripts only send these, `apply_player_commands` carries them out.
+// Towers are referred to by their position, entities differ between runs
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum PlayerCommand {
+  PlaceTower { tower_type: TowerType, position: Vec3 },
+  Upgrade { tower: Vec3, path_index: usize },
+  Sell { tower: Vec3 },
+  SetTargeting { tower: Vec3, target: TargetingPriority },
+  CallWave,
+}
+
+// Apply the commands sent since the last step, at the start of the step. Commands the
+// player can't afford or that name a tower that doesn't exist are ignored
+#[allow(clippy::too_many_arguments)]
+pub fn apply_player_commands(
+  mut commands: Commands,
+  mut player_commands: EventReader<PlayerCommand>,
+  mut recorder: ReplayRecorder,
+  assets: Res<GameAssets>,
+  game_data: Res<GameData>,
+  (tower_stats, upgrades): (Res<Assets<TowerTypeStats>>, Res<Assets<Upgrades>>),
+  map: Res<Map>,
+  mut player: Query<&mut Player>,
+  mut towers: Query<(Entity, &mut Tower, &TowerType, &Transform)>,
+  tower_ui: Query<Entity, With<TowerUpgradeUI>>,
+  (mut meshes, mut materials): (ResMut<Assets<Mesh>>, ResMut<Assets<ColorMaterial>>),
+  mut tower_range_radius: Query<&mut Mesh2dHandle>,
+  mut call_wave_writer: EventWriter<CallWaveEvent>,
+) {
+  let (Some(tower_stats), Some(upgrades)) = (
+    tower_stats.get(&game_data.tower_type_stats),
+    upgrades.get(&game_data.tower_upgrades),
+  )
+    else { return; };
+  let mut player = player.single_mut();
+  // Towers placed or sold by earlier commands of this step are still (not) in the query
+  let mut placed = vec![];
+  let mut sold = vec![];
+
+  for command in player_commands.iter() {
+    let applied = match command {
+      PlayerCommand::PlaceTower { tower_type, position } => {
+        let price = tower_stats.tower[tower_type].tower.price as usize;
+        let existing = towers.iter().map(|(.., transform)| transform.translation);
+        let can_place = player.money >= price
+          && can_place_tower(&map, *tower_type, *position, existing.chain(placed.iter().copied()));
+
+        if can_place {
+          placed.push(*position);
+          buy_tower(
+            &mut commands,
+            *tower_type,
+            *position,
+            &mut player,
+            &assets,
+            &mut meshes,
+            &mut materials,
+            tower_stats,
+          );
+        }
+        can_place
+      }
+      PlayerCommand::Upgrade { tower, path_index } => {
+        match tower_at(&towers, *tower).and_then(|entity| towers.get_mut(entity).ok()) {
+          Some((_, mut tower, tower_type, _)) => upgrade_tower(
+            &mut tower,
+            tower_type,
+            *path_index,
+            &mut player,
+            upgrades,
+            &mut meshes,
+            &mut tower_range_radius,
+          ),
+          None => false,
+        }
+      }
+      PlayerCommand::Sell { tower } => {
+        match tower_at(&towers, *tower).and_then(|entity| towers.get(entity).ok()) {
+          Some((entity, tower, ..)) if !sold.contains(&entity) => {
+            sold.push(entity);
+            sell_tower(&mut commands, entity, tower, &mut player, tower_ui.iter());
+            true
+          }
+          _ => false,
+        }
+      }
+      PlayerCommand::SetTargeting { tower, target } => {
+        match tower_at(&towers, *tower).and_then(|entity| towers.get_mut(entity).ok()) {
+          Some((_, mut tower, ..)) => {
+            tower.target = target.clone();
+            true
+          }
+          None => false,
+        }
+      }
+      PlayerCommand::CallWave => {
+        call_wave_writer.send(CallWaveEvent);
+        true
+      }
+    };
+
+    if applied {
+      recorder.record(command.clone());
+    }
+  }
+}
+
+fn tower_at(
+  towers: &Query<(Entity, &mut Tower, &TowerType, &Transform)>,
+  position: Vec3,
+) -> Option<Entity> {
+  towers
+    .iter()
+    .find(|(.., transform)| transform.translation == position)
+    .map(|(entity, ..)| entity)
+}
+
+#[cfg(test)]
+#[path = "simulation/player_command_tests.rs"]
+mod tests;
@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+use rand::rngs::Xoshiro256PlusPlus;
+use rand::seq::IteratorRandom;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+// Seed used by levels started from the menu
+pub const DEFAULT_SEED: u64 = 0;
+
+// Every random choice the simulation makes comes from here, so a level replayed with the
+// same seed and the same player actions plays out the same way. Xoshiro's output is the
+// same on every platform and rand version, unlike `StdRng`
+#[derive(Resource, Serialize, Deserialize, Clone)]
+pub struct SimulationRng {
+  pub seed: u64,
+  rng: Xoshiro256PlusPlus,
+}
+
+impl Default for SimulationRng {
+  fn default() -> Self {
+    Self::new(DEFAULT_SEED)
+  }
+}
+
+impl SimulationRng {
+  pub fn new(seed: u64) -> Self {
+    Self {
+      seed,
+      rng: Xoshiro256PlusPlus::seed_from_u64(seed),
+    }
+  }
+
+  // Start the sequence over, done whenever a level is (re)started
+  pub fn reset(&mut self) {
+    self.rng = Xoshiro256PlusPlus::seed_from_u64(self.seed);
+  }
+
+  pub fn choose<I: Iterator>(&mut self, iter: I) -> Option<I::Item> {
+    iter.choose(&mut self.rng)
+  }
+}
+
+pub fn reset_simulation_rng(mut rng: ResMut<SimulationRng>) {
+  rng.reset();
+}
+
+#[cfg(test)]
+#[path = "simulation/rng_tests.rs"]
+mod tests;
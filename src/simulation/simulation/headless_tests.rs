@@ -0,0 +1,191 @@
+use super::*;
+
+fn level1_scenario(towers: Vec<ScriptedTower>, time_limit: f32) -> Scenario {
+    Scenario {
+        map: "assets/data/level1.map.ron".to_string(),
+        waves: "assets/data/enemy.waves.ron".to_string(),
+        enemy_stats: default_enemy_stats(),
+        tower_stats: default_tower_stats(),
+        tower_upgrades: default_tower_upgrades(),
+        towers,
+        time_limit,
+        seed: 0,
+        endless: false,
+    }
+}
+
+fn tower(tower_type: TowerType, column: usize, row: usize) -> ScriptedTower {
+    ScriptedTower {
+        tower_type,
+        column,
+        row,
+        wave: 0,
+    }
+}
+
+#[test]
+fn test_scenario_defaults() {
+    let scenario = Scenario::from_ron(
+        r#"(map: "a.map.ron", waves: "b.waves.ron", towers: [(tower_type: Fire, column: 1, row: 2)])"#,
+    )
+    .unwrap();
+    assert_eq!(scenario.enemy_stats, default_enemy_stats());
+    assert_eq!(scenario.tower_stats, default_tower_stats());
+    assert_eq!(scenario.tower_upgrades, default_tower_upgrades());
+    assert_eq!(scenario.towers, vec![tower(TowerType::Fire, 1, 2)]);
+    assert_eq!(scenario.time_limit, default_time_limit());
+    assert_eq!(scenario.seed, 0);
+}
+
+#[test]
+fn test_example_scenario_loads() {
+    let scenario = Scenario::load("scenarios/level1.scenario.ron").unwrap();
+    assert_eq!(scenario.towers.len(), 4);
+}
+
+#[test]
+fn test_scripted_tower_position_counts_rows_from_top() {
+    let mut map: Map = ron::from_str(include_str!("../../../assets/data/level1.map.ron")).unwrap();
+    map.prepare();
+
+    let fire = tower(TowerType::Fire, 4, 7);
+    assert_eq!(fire.position(&map), Vec3::new(320., 80., 0.));
+    assert_eq!(map.tile_at(fire.position(&map)), Some(&Tile::Grass));
+
+    let spawn = tower(TowerType::Fire, 3, 8);
+    assert_eq!(map.tile_at(spawn.position(&map)), Some(&Tile::Spawn));
+}
+
+#[test]
+fn test_missing_file_is_an_error() {
+    let mut scenario = level1_scenario(vec![], 1.);
+    scenario.map = "assets/data/missing.map.ron".to_string();
+    assert!(run_headless(&scenario).is_err());
+}
+
+#[test]
+fn test_headless_run_places_towers_and_fights() {
+    let scenario = level1_scenario(
+        vec![
+            tower(TowerType::Fire, 4, 7),
+            // Path tile and an already taken tile are rejected
+            tower(TowerType::Fire, 3, 7),
+            tower(TowerType::Ice, 4, 7),
+            // Not enough money until enemies have been killed
+            tower(TowerType::Archmage, 9, 6),
+        ],
+        20.,
+    );
+    let report = run_headless(&scenario).unwrap();
+
+    assert_eq!(report.outcome, SimulationOutcome::TimeLimit);
+    assert!((report.elapsed - 20.).abs() < 0.1);
+    assert_eq!(report.towers.len(), 1);
+    assert!(report.towers[0].shots_fired > 0);
+    assert!(report.towers[0].total_damage > 0);
+    assert_eq!(
+        report.unplaced_towers,
+        vec![
+            tower(TowerType::Fire, 3, 7),
+            tower(TowerType::Ice, 4, 7),
+            tower(TowerType::Archmage, 9, 6),
+        ]
+    );
+
+    let json = report.to_json().unwrap();
+    assert!(json.contains("\"base_health\""));
+    assert!(json.contains("\"tower_type\": \"Fire\""));
+}
+
+#[test]
+fn test_headless_run_without_towers_loses() {
+    let report = run_headless(&level1_scenario(vec![], 600.)).unwrap();
+    assert_eq!(report.outcome, SimulationOutcome::GameOver);
+    assert_eq!(report.base_health, 0);
+    assert!(report.towers.is_empty());
+    assert_eq!(report.endless_round, None);
+}
+
+fn example_towers() -> Vec<ScriptedTower> {
+    Scenario::load("scenarios/level1.scenario.ron").unwrap().towers
+}
+
+// Run `steps` simulation steps, `steps_per_frame` of them every frame
+fn run_at_frame_rate(scenario: &Scenario, steps: u32, steps_per_frame: u32) -> SimulationReport {
+    let mut app = headless_app(scenario).unwrap();
+    let start = app.world.resource::<Time>().startup();
+    let frame_time = SIMULATION_TIMESTEP * steps_per_frame;
+
+    for frame in 0..=steps / steps_per_frame {
+        app.insert_resource(TimeUpdateStrategy::ManualInstant(start + frame_time * frame));
+        app.update();
+    }
+
+    simulation_report(&mut app.world, SimulationOutcome::TimeLimit)
+}
+
+#[test]
+fn test_headless_runs_are_deterministic() {
+    let scenario = level1_scenario(example_towers(), 90.);
+    let first = run_headless(&scenario).unwrap().to_json().unwrap();
+    let second = run_headless(&scenario).unwrap().to_json().unwrap();
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_outcome_does_not_depend_on_frame_rate() {
+    let scenario = level1_scenario(example_towers(), 120.);
+    let every_step = run_at_frame_rate(&scenario, 7200, 1).to_json().unwrap();
+    let every_third_step = run_at_frame_rate(&scenario, 7200, 3).to_json().unwrap();
+    assert_eq!(every_step, every_third_step);
+}
+
+#[test]
+fn test_invalid_map_is_an_error() {
+    let path = std::env::temp_dir().join(format!("headless_test_{}.map.ron", std::process::id()));
+    std::fs::write(
+        &path,
+        "(width: 3, height: 1, tiles: [[Spawn, Path([0]), Grass]], tile_size: 80, checkpoints: [])",
+    )
+    .unwrap();
+    let scenario = Scenario {
+        map: path.to_str().unwrap().to_string(),
+        ..level1_scenario(vec![], 10.)
+    };
+
+    let error = run_headless(&scenario).unwrap_err().to_string();
+    std::fs::remove_file(&path).unwrap();
+    assert!(error.contains("path 0 touches 0 ends instead of one"), "{}", error);
+}
+
+#[test]
+fn test_invalid_waves_are_an_error() {
+    let path = std::env::temp_dir().join(format!("headless_test_{}.waves.ron", std::process::id()));
+    std::fs::write(&path, "(waves: [(enemies: [], current: 0)], current: 0)").unwrap();
+    let scenario = Scenario {
+        waves: path.to_str().unwrap().to_string(),
+        ..level1_scenario(vec![], 10.)
+    };
+
+    let error = run_headless(&scenario).unwrap_err().to_string();
+    std::fs::remove_file(&path).unwrap();
+    assert!(error.contains("wave 0 has no enemies"), "{}", error);
+}
+
+#[test]
+fn test_endless_scenario_keeps_spawning_waves() {
+    let path = std::env::temp_dir().join(format!("headless_endless_{}.waves.ron", std::process::id()));
+    std::fs::write(&path, "(waves: [(groups: [(type: Green, count: 1)])])").unwrap();
+    let scenario = Scenario {
+        waves: path.to_str().unwrap().to_string(),
+        endless: true,
+        ..level1_scenario(vec![], 60.)
+    };
+
+    let report = run_headless(&scenario).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_ne!(report.outcome, SimulationOutcome::Victory);
+    assert!(report.waves_survived > 1, "{:?}", report);
+    // The only scripted wave is followed by endless rounds
+    assert_eq!(report.endless_round, Some(report.waves_survived));
+}
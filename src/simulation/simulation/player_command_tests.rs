@@ -0,0 +1,137 @@
+use super::*;
+use bevy::time::TimeUpdateStrategy;
+
+use crate::{headless_app, Scenario, SIMULATION_TIMESTEP};
+
+// Level 1 without any scripted towers, in gameplay and ready to take commands
+fn level1_app() -> App {
+    let scenario = Scenario::from_ron(
+        r#"(map: "assets/data/level1.map.ron", waves: "assets/data/enemy.waves.ron")"#,
+    )
+    .unwrap();
+    let mut app = headless_app(&scenario).unwrap();
+    let start = app.world.resource::<Time>().startup();
+    // The first frame enters gameplay and only starts the clock
+    app.insert_resource(TimeUpdateStrategy::ManualInstant(start));
+    app.update();
+    app
+}
+
+// Send the commands and run the step that carries them out
+fn send(app: &mut App, commands: Vec<PlayerCommand>) {
+    for command in commands {
+        app.world.send_event(command);
+    }
+    let last_update = app.world.resource::<Time>().last_update().unwrap();
+    app.insert_resource(TimeUpdateStrategy::ManualInstant(last_update + SIMULATION_TIMESTEP));
+    app.update();
+}
+
+fn place(tower_type: TowerType, x: f32, y: f32) -> PlayerCommand {
+    PlayerCommand::PlaceTower {
+        tower_type,
+        position: Vec3::new(x, y, 0.),
+    }
+}
+
+fn money(app: &mut App) -> usize {
+    app.world.query::<&Player>().single(&app.world).money
+}
+
+fn towers(app: &mut App) -> Vec<(Vec3, Tower)> {
+    app.world
+        .query::<(&Transform, &Tower)>()
+        .iter(&app.world)
+        .map(|(transform, tower)| (transform.translation, tower.clone()))
+        .collect()
+}
+
+#[test]
+fn test_place_tower_pays_for_it() {
+    let mut app = level1_app();
+    send(&mut app, vec![place(TowerType::Fire, 0., 0.)]);
+
+    let towers = towers(&mut app);
+    assert_eq!(towers.len(), 1);
+    assert_eq!(towers[0].0, Vec3::ZERO);
+    assert_eq!(money(&mut app), 0);
+}
+
+#[test]
+fn test_place_tower_the_player_cannot_afford_is_ignored() {
+    let mut app = level1_app();
+    send(
+        &mut app,
+        vec![
+            place(TowerType::Archmage, 0., 0.),
+            place(TowerType::Fire, 0., 0.),
+            place(TowerType::Ice, 160., 0.),
+        ],
+    );
+
+    assert_eq!(towers(&mut app).len(), 1);
+    assert_eq!(money(&mut app), 0);
+}
+
+#[test]
+fn test_place_tower_on_a_taken_spot_or_the_path_is_ignored() {
+    let mut app = level1_app();
+    app.world.query::<&mut Player>().single_mut(&mut app.world).money = 1000;
+    send(
+        &mut app,
+        vec![
+            place(TowerType::Fire, 0., 0.),
+            // Too close to the tower placed by the command before it in the same step
+            place(TowerType::Ice, 10., 0.),
+            // Spawn tile
+            place(TowerType::Fire, 240., 0.),
+        ],
+    );
+
+    assert_eq!(towers(&mut app).len(), 1);
+    assert_eq!(money(&mut app), 900);
+}
+
+#[test]
+fn test_sell_refunds_the_tower_once() {
+    let mut app = level1_app();
+    send(&mut app, vec![place(TowerType::Fire, 0., 0.)]);
+    let sell = PlayerCommand::Sell { tower: Vec3::ZERO };
+    send(&mut app, vec![sell.clone(), sell]);
+
+    assert!(towers(&mut app).is_empty());
+    assert_eq!(money(&mut app), 33);
+}
+
+#[test]
+fn test_set_targeting() {
+    let mut app = level1_app();
+    send(&mut app, vec![place(TowerType::Fire, 0., 0.)]);
+    send(
+        &mut app,
+        vec![PlayerCommand::SetTargeting {
+            tower: Vec3::ZERO,
+            target: TargetingPriority::STRONG,
+        }],
+    );
+
+    assert_eq!(towers(&mut app)[0].1.target, TargetingPriority::STRONG);
+}
+
+#[test]
+fn test_commands_for_missing_towers_are_ignored() {
+    let mut app = level1_app();
+    send(
+        &mut app,
+        vec![
+            PlayerCommand::Upgrade {
+                tower: Vec3::ZERO,
+                path_index: 0,
+            },
+            PlayerCommand::Sell { tower: Vec3::ZERO },
+        ],
+    );
+
+    assert!(towers(&mut app).is_empty());
+    assert_eq!(money(&mut app), 100);
+}
@@ -0,0 +1,33 @@
+use super::*;
+
+fn choices(rng: &mut SimulationRng) -> Vec<u32> {
+    (0..20).map(|_| rng.choose(0..100).unwrap()).collect()
+}
+
+#[test]
+fn test_same_seed_same_choices() {
+    let mut a = SimulationRng::new(42);
+    let mut b = SimulationRng::new(42);
+    assert_eq!(choices(&mut a), choices(&mut b));
+}
+
+#[test]
+fn test_different_seed_different_choices() {
+    let mut a = SimulationRng::new(1);
+    let mut b = SimulationRng::new(2);
+    assert_ne!(choices(&mut a), choices(&mut b));
+}
+
+#[test]
+fn test_reset_repeats_choices() {
+    let mut rng = SimulationRng::new(7);
+    let first = choices(&mut rng);
+    rng.reset();
+    assert_eq!(choices(&mut rng), first);
+}
+
+#[test]
+fn test_choose_from_empty_is_none() {
+    let mut rng = SimulationRng::default();
+    assert_eq!(rng.choose(std::iter::empty::<u32>()), None);
+}
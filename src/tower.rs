@@ -4,6 +4,9 @@ pub use tower::*;
 mod bullet;
 pub use bullet::*;
 
+mod projectile;
+pub use projectile::*;
+
 mod tower_type;
 pub use tower_type::*;
 
@@ -16,6 +19,9 @@ pub use tower_selection::*;
 mod tower_upgrade;
 pub use tower_upgrade::*;
 
+mod tower_lifetime_stats;
+pub use tower_lifetime_stats::*;
+
 mod tower_ui;
 pub use tower_ui::*;
 
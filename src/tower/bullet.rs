@@ -3,7 +3,8 @@ use bevy::sprite::collide_aabb::collide;
 
 use crate::enemy::*;
 use crate::movement::*;
-use crate::{GameState, Tower};
+use crate::tower::*;
+use crate::GameplaySet;
 
 pub struct BulletPlugin;
 
@@ -11,7 +12,11 @@ impl Plugin for BulletPlugin {
   fn build(&self, app: &mut App) {
     app
       .register_type::<Bullet>()
-      .add_systems((despawn_bullets, bullet_enemy_collision).in_set(OnUpdate(GameState::Gameplay)));
+      .add_systems(
+        (despawn_bullets, steer_homing_bullets, bullet_enemy_collision)
+          .in_set(GameplaySet::Simulation)
+          .in_schedule(CoreSchedule::FixedUpdate),
+      );
   }
 }
 
@@ -28,15 +33,29 @@ pub struct BulletBundle {
 pub struct Bullet {
   pub damage: u32,
   pub lifetime: Timer, // !!! fix?
+  pub effect: ProjectileEffect,
+  // Number of enemies the bullet flies through before despawning
+  pub pierce: u32,
+  // Enemy the bullet steers towards
+  pub homing_target: Option<Entity>,
+  // Enemies the bullet has already hit, so piercing bullets don't hit them twice
+  pub hit_enemies: Vec<Entity>,
+}
+
+impl Bullet {
+  // Every bullet hits at least one enemy
+  pub fn is_spent(&self) -> bool {
+    self.hit_enemies.len() >= self.pierce.max(1) as usize
+  }
 }
 
 fn despawn_bullets(
   mut commands: Commands,
   mut bullets: Query<(Entity, &mut Bullet)>,
-  time: Res<Time>,
+  fixed_time: Res<FixedTime>,
 ) {
   for (entity, mut bullet) in &mut bullets {
-    bullet.lifetime.tick(time.delta());
+    bullet.lifetime.tick(fixed_time.period);
     // If the lifetime timer finished, despawn bullet
     if bullet.lifetime.finished() {
       // Despawn entities and their children
@@ -45,35 +64,126 @@ fn despawn_bullets(
   }
 }
 
+fn steer_homing_bullets(
+  mut bullets: Query<(&Bullet, &Parent, &GlobalTransform, &mut Movement)>,
+  towers: Query<&GlobalTransform, With<Tower>>,
+  enemies: Query<&GlobalTransform, With<Enemy>>,
+) {
+  for (bullet, tower_parent, bullet_transform, mut movement) in &mut bullets {
+    let Some(target) = bullet.homing_target
+      else { continue; };
+    // Piercing bullets fly on past the enemy they were homing in on
+    if bullet.hit_enemies.contains(&target) {
+      continue;
+    }
+    // Bullets keep their last direction once the target is gone
+    let (Ok(tower_transform), Ok(enemy_transform)) =
+      (towers.get(tower_parent.get()), enemies.get(target))
+      else { continue; };
+
+    let direction = (enemy_transform.translation() - bullet_transform.translation())
+      .truncate()
+      .extend(0.);
+    if direction == Vec3::ZERO {
+      continue;
+    }
+    // Bullets are children of the tower, so they move in the tower's space
+    movement.direction = tower_transform.affine().inverse().transform_vector3(direction);
+  }
+}
+
+// Bullet and enemy hitboxes
+const BULLET_SIZE: Vec2 = Vec2::new(40., 22.);
+const ENEMY_SIZE: Vec2 = Vec2::new(30., 30.);
+
 fn bullet_enemy_collision(
   mut commands: Commands,
-  bullets: Query<(Entity, &Bullet, &Parent, &GlobalTransform)>,
-  mut enemies: Query<(&mut Enemy, &Transform)>,
-  mut towers: Query<&mut Tower>,
+  mut bullets: Query<(Entity, &mut Bullet, &Parent, &GlobalTransform)>,
+  mut enemies: Query<(
+    Entity,
+    &mut Enemy,
+    &EnemyType,
+    &mut EnemyAbilities,
+    &mut StatusEffects,
+  )>,
+  spatial_hash: Res<SpatialHash>,
+  mut damage_writer: EventWriter<TowerDamageEvent>,
 ) {
-  for (bullet_entity, bullet, tower_parent, bullet_transform) in &bullets {
-    for (mut enemy, enemy_transform) in &mut enemies {
-      if collide(
-        bullet_transform.translation(),
-        Vec2::new(40., 22.),
-        enemy_transform.translation,
-        Vec2::new(30., 30.),
-      )
-      .is_some()
-      {
-        // Update tower's total damage
-        let mut tower = towers.get_mut(tower_parent.get()).unwrap();
-        if enemy.health >= bullet.damage as i32 {
-          tower.total_damage += bullet.damage;
-        } else {
-          tower.total_damage += enemy.health as u32;
-        }
-
-        // Despawn bullet upon hit and damage enemy
-        commands.entity(bullet_entity).despawn_recursive();
-        enemy.health -= bullet.damage as i32;
-        break;
+  // Furthest an enemy's centre can be from a bullet's centre while their hitboxes overlap
+  let collision_radius = ((BULLET_SIZE + ENEMY_SIZE) / 2.).length();
+
+  for (bullet_entity, mut bullet, tower_parent, bullet_transform) in &mut bullets {
+    let bullet_position = bullet_transform.translation();
+    // Enemies killed earlier this frame don't stop the bullet
+    let hit = spatial_hash.query_radius(bullet_position, collision_radius).find(|(entity, position)| {
+      !bullet.hit_enemies.contains(entity)
+        && enemies
+          .get(*entity)
+          .is_ok_and(|(_, enemy, ..)| enemy.health > 0)
+        && collide(bullet_position, BULLET_SIZE, *position, ENEMY_SIZE).is_some()
+    });
+    let Some((hit, impact)) = hit
+      else { continue; };
+    bullet.hit_enemies.push(hit);
+
+    // Enemy that was hit comes first, followed by the ones the effect spreads to
+    let mut targets = vec![hit];
+    match bullet.effect {
+      ProjectileEffect::Splash { radius } => {
+        let nearby = spatial_hash.query_radius(impact, radius).collect::<Vec<_>>();
+        targets.extend(splash_targets(impact, radius, hit, &nearby))
+      }
+      ProjectileEffect::Chain { jumps, range } => {
+        // Furthest the chain can reach from the impact
+        let nearby = spatial_hash
+          .query_radius(impact, range * jumps as f32)
+          .collect::<Vec<_>>();
+        targets.extend(chain_targets(impact, jumps, range, hit, &nearby))
+      }
+      _ => {}
+    }
+
+    let tower = tower_parent.get();
+    // Only the first enemy a piercing bullet goes through counts towards its accuracy
+    let first_hit = bullet.hit_enemies.len() == 1;
+    for target in targets {
+      let Ok((_, mut enemy, enemy_type, mut abilities, mut status_effects)) =
+        enemies.get_mut(target)
+        else { continue; };
+      if enemy.health <= 0 {
+        continue;
       }
+
+      // Shield and armor reduce the damage dealt
+      let armor_piercing = matches!(bullet.effect, ProjectileEffect::ArmorPiercing);
+      let damage = abilities.absorb_hit(bullet.damage, armor_piercing);
+
+      // Damage past the enemy's remaining health doesn't count towards the tower's stats
+      enemy.last_hit_by = Some(tower);
+      damage_writer.send(TowerDamageEvent {
+        tower,
+        enemy_type: *enemy_type,
+        damage: damage.min(enemy.health as u32),
+        direct_hit: first_hit && target == hit,
+      });
+      enemy.health -= damage as i32;
+
+      match bullet.effect {
+        ProjectileEffect::Slow {
+          speed_multiplier,
+          duration,
+        } => status_effects.apply_slow(speed_multiplier, duration),
+        ProjectileEffect::Poison {
+          damage_per_second,
+          duration,
+        } => status_effects.apply_poison(damage_per_second, duration, tower),
+        _ => {}
+      }
+    }
+
+    // Despawn bullet once it has hit as many enemies as it can
+    if bullet.is_spent() {
+      commands.entity(bullet_entity).despawn_recursive();
     }
   }
 }
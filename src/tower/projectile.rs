@@ -0,0 +1,126 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// What happens when a tower's bullet hits an enemy, read from `stats.tower_stats.ron`
+#[derive(Reflect, FromReflect, Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ProjectileEffect {
+  // Only damages the enemy that was hit
+  #[default]
+  None,
+  // Also damages every enemy within `radius` of the impact
+  Splash { radius: f32 },
+  // Multiplies the enemy's speed for `duration` seconds
+  Slow { speed_multiplier: f32, duration: f32 },
+  // Keeps damaging the enemy for `duration` seconds
+  Poison { damage_per_second: f32, duration: f32 },
+  // Ignores the enemy's armor
+  ArmorPiercing,
+  // Jumps from the enemy that was hit to up to `jumps` other enemies within `range` of each other
+  Chain { jumps: u32, range: f32 },
+}
+
+// How a tower's bullets find their target, read from `stats.tower_stats.ron`
+#[derive(Reflect, FromReflect, Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ProjectileMode {
+  // Fly straight at where the enemy was when the bullet was fired
+  #[default]
+  Straight,
+  // Steer towards the enemy every frame
+  Homing,
+  // Fly straight at where the enemy will be when the bullet gets there
+  Predictive,
+}
+
+// Times the intercept point is refined, each one gets closer to where bullet and enemy meet
+const INTERCEPT_ITERATIONS: usize = 4;
+
+// Enemies around the impact, other than the one that was hit
+pub fn splash_targets(
+  impact: Vec3,
+  radius: f32,
+  hit: Entity,
+  enemies: &[(Entity, Vec3)],
+) -> Vec<Entity> {
+  enemies
+    .iter()
+    .filter(|(entity, position)| *entity != hit && position.distance(impact) <= radius)
+    .map(|(entity, _)| *entity)
+    .collect()
+}
+
+// Each jump goes to the closest enemy that hasn't been hit yet
+pub fn chain_targets(
+  impact: Vec3,
+  jumps: u32,
+  range: f32,
+  hit: Entity,
+  enemies: &[(Entity, Vec3)],
+) -> Vec<Entity> {
+  let mut targets = vec![];
+  let mut last_position = impact;
+
+  for _ in 0..jumps {
+    let next = enemies
+      .iter()
+      .filter(|(entity, position)| {
+        *entity != hit && !targets.contains(entity) && position.distance(last_position) <= range
+      })
+      .min_by(|(_, a), (_, b)| a.distance(last_position).total_cmp(&b.distance(last_position)));
+
+    let Some((entity, position)) = next
+      else { break; };
+    targets.push(*entity);
+    last_position = *position;
+  }
+
+  targets
+}
+
+// Where an enemy walking along its path will be after `time` seconds
+pub fn predict_position(
+  position: Vec3,
+  speed: f32,
+  checkpoints: &[Vec3],
+  next_checkpoint: usize,
+  time: f32,
+) -> Vec3 {
+  let mut position = position;
+  let mut distance = speed * time;
+
+  for checkpoint in checkpoints.iter().skip(next_checkpoint) {
+    let to_checkpoint = position.distance(*checkpoint);
+    if to_checkpoint >= distance {
+      return position + (*checkpoint - position).normalize_or_zero() * distance;
+    }
+    distance -= to_checkpoint;
+    position = *checkpoint;
+  }
+
+  // Enemy reaches the base before the bullet gets there
+  position
+}
+
+// Point where a bullet fired from `origin` meets an enemy walking along its path
+pub fn intercept_point(
+  origin: Vec3,
+  projectile_speed: f32,
+  position: Vec3,
+  speed: f32,
+  checkpoints: &[Vec3],
+  next_checkpoint: usize,
+) -> Vec3 {
+  if projectile_speed <= 0. {
+    return position;
+  }
+
+  let mut target = position;
+  for _ in 0..INTERCEPT_ITERATIONS {
+    let time = origin.distance(target) / projectile_speed;
+    target = predict_position(position, speed, checkpoints, next_checkpoint, time);
+  }
+  target
+}
+
+#[cfg(test)]
+#[path = "tower/projectile_tests.rs"]
+mod tests;
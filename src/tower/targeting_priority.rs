@@ -1,12 +1,12 @@
 use bevy::prelude::*;
 use bevy::utils::FloatOrd;
-use rand::seq::IteratorRandom;
 use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
 use crate::enemy::*;
 use crate::movement::*;
+use crate::SimulationRng;
 
 #[derive(
   EnumIter, Reflect, Clone, Debug, Component, Default, PartialEq, Serialize, Deserialize,
@@ -43,29 +43,30 @@ impl TargetingPriority {
   }
 }
 
-pub fn get_enemy_direction(
-  enemies: &Query<(&GlobalTransform, &Enemy, &Movement)>,
+// Enemy the tower should shoot at and where it is
+pub fn get_enemy_target(
+  spatial_hash: &SpatialHash,
+  enemies: &Query<(Entity, &GlobalTransform, &Enemy, &Movement, &Path)>,
   bullet_spawn_pos: Vec3,
   tower_range: u32,
   tower_targeting_priority: &TargetingPriority,
-) -> Option<Vec3> {
-  let enemy_filtered_query = enemies
-    .iter()
-    // Filter the enemies that are in the tower's range
-    .filter(|(enemy_transform, ..)| {
-      Vec3::distance(enemy_transform.translation(), bullet_spawn_pos) <= tower_range as f32
-    });
+  rng: &mut SimulationRng,
+) -> Option<(Entity, Vec3)> {
+  let enemy_filtered_query = spatial_hash
+    // Only look at the enemies that are in the tower's range
+    .query_radius(bullet_spawn_pos, tower_range as f32)
+    .filter_map(|(entity, _)| enemies.get(entity).ok());
 
   let enemy = match tower_targeting_priority {
     TargetingPriority::FIRST => enemy_filtered_query
       // Find first enemy that is closest to the base
-      .max_by_key(|(.., movement)| FloatOrd(movement.distance_travelled)),
+      .max_by_key(|(.., movement, _)| FloatOrd(movement.distance_travelled)),
     TargetingPriority::LAST => enemy_filtered_query
       // Find first enemy that is closest to the base
-      .min_by_key(|(.., movement)| FloatOrd(movement.distance_travelled)),
+      .min_by_key(|(.., movement, _)| FloatOrd(movement.distance_travelled)),
     TargetingPriority::CLOSE => enemy_filtered_query
       // Find enemy that is closest to the tower
-      .min_by_key(|(enemy_transform, ..)| {
+      .min_by_key(|(_, enemy_transform, ..)| {
         FloatOrd(Vec3::distance(
           enemy_transform.translation(),
           bullet_spawn_pos,
@@ -73,7 +74,7 @@ pub fn get_enemy_direction(
       }),
     TargetingPriority::FAR => enemy_filtered_query
       // Find enemy that is the farthest away from the tower
-      .max_by_key(|(enemy_transform, ..)| {
+      .max_by_key(|(_, enemy_transform, ..)| {
         FloatOrd(Vec3::distance(
           enemy_transform.translation(),
           bullet_spawn_pos,
@@ -81,20 +82,15 @@ pub fn get_enemy_direction(
       }),
     TargetingPriority::STRONG => enemy_filtered_query
       // Find the strongest enemy
-      .max_by_key(|(_, enemy, ..)| FloatOrd(enemy.health as f32)),
+      .max_by_key(|(_, _, enemy, ..)| FloatOrd(enemy.health as f32)),
     TargetingPriority::WEAK => enemy_filtered_query
       // Find the weakest enemy
-      .min_by_key(|(_, enemy, ..)| FloatOrd(enemy.health as f32)),
-    TargetingPriority::RANDOM => enemy_filtered_query
-      // Choose a random enemy
-      .choose(&mut rand::rng())
+      .min_by_key(|(_, _, enemy, ..)| FloatOrd(enemy.health as f32)),
+    // Choose a random enemy
+    TargetingPriority::RANDOM => rng.choose(enemy_filtered_query),
   };
 
-  if let Some((enemy, ..)) = enemy {
-    // return direction
-    return Option::from(enemy.translation() - bullet_spawn_pos);
-  }
-  None
+  enemy.map(|(entity, enemy_transform, ..)| (entity, enemy_transform.translation()))
 }
 
 #[cfg(test)]
@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 use bevy::sprite::Mesh2dHandle;
+use bevy::utils::FloatOrd;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
@@ -7,7 +8,7 @@ use crate::assets::*;
 use crate::enemy::*;
 use crate::movement::*;
 use crate::tower::*;
-use crate::GameState;
+use crate::{GameplaySet, Map, SimulationRng};
 
 pub struct TowerPlugin;
 
@@ -16,7 +17,11 @@ impl Plugin for TowerPlugin {
     app
       .register_type::<Tower>()
       .register_type::<TargetingPriority>()
-      .add_system(tower_shooting.in_set(OnUpdate(GameState::Gameplay)));
+      .add_system(
+        tower_shooting
+          .in_set(GameplaySet::Simulation)
+          .in_schedule(CoreSchedule::FixedUpdate),
+      );
   }
 }
 
@@ -24,9 +29,22 @@ impl Plugin for TowerPlugin {
 pub struct TowerBundle {
   pub tower_type: TowerType,
   pub tower: Tower,
+  #[serde(default)]
+  pub lifetime_stats: TowerLifetimeStats,
   pub name: Name,
 }
 
+pub const DEFAULT_PIERCE: u32 = 1;
+pub const DEFAULT_PROJECTILE_SPEED: f32 = 1500.;
+
+fn default_pierce() -> u32 {
+  DEFAULT_PIERCE
+}
+
+fn default_projectile_speed() -> f32 {
+  DEFAULT_PROJECTILE_SPEED
+}
+
 //#[derive(Component)] // !!!Debugging
 #[derive(Reflect, Clone, Component, Default, Serialize, Deserialize)]
 #[reflect(Component)]
@@ -35,6 +53,11 @@ pub struct Tower {
   pub damage: u32,
   pub attack_speed: f32,
   pub range: u32,
+  // Number of enemies each bullet hits before it is used up
+  #[serde(default = "default_pierce")]
+  pub pierce: u32,
+  #[serde(default = "default_projectile_speed")]
+  pub projectile_speed: f32,
   pub price: u32,
   pub sell_price: u32,
   pub upgrades: TowerUpgrades,
@@ -42,6 +65,11 @@ pub struct Tower {
   pub shooting_timer: Timer,
   pub total_spent: u32,
   pub total_damage: u32,
+  // What the tower's bullets do when they hit
+  #[serde(default)]
+  pub projectile: ProjectileEffect,
+  #[serde(default)]
+  pub projectile_mode: ProjectileMode,
   // Flag to stop timer from counting when there are no enemies
   pub first_enemy_appeared: bool,
 }
@@ -59,6 +87,8 @@ impl Tower {
       damage,
       attack_speed,
       range,
+      pierce: DEFAULT_PIERCE,
+      projectile_speed: DEFAULT_PROJECTILE_SPEED,
       price,
       total_spent: price,
       sell_price: price / 3,
@@ -98,6 +128,8 @@ impl Tower {
             radius.0 = meshes.add(shape::Circle::new(self.range as f32).into());
           }
         }
+        TowerStat::Pierce => self.pierce += *v as u32,
+        TowerStat::ProjectileSpeed => self.projectile_speed += *v as f32,
       }
     }
 
@@ -105,62 +137,79 @@ impl Tower {
   }
 }
 
+// Spawn a new or a saved tower, both go through here so they end up with the same components
 pub fn spawn_tower(
   commands: &mut Commands,
-  tower_type: TowerType,
+  tower: &TowerBundle,
   assets: &GameAssets,
   position: Vec3,
-  meshes: &mut Assets<Mesh>,
-  materials: &mut Assets<ColorMaterial>,
-  tower_stats: &TowerTypeStats,
-) {
+) -> Entity {
   commands
-    .spawn(tower_type.get_tower(tower_stats))
-    .insert(tower_type.get_sprite_sheet_bundle(assets, position))
-    .with_children(|commands| {
-      commands
-        .spawn(spawn_tower_range(
-          meshes,
-          materials,
-          tower_stats.tower[&tower_type].tower.range,
-        ))
-        .insert(Name::new("Tower Range"))
-        .insert(TowerUpgradeUI);
-    });
-
-  // Spawn Tower UI - Targeting priority, Selling & Upgrades
-  spawn_tower_ui(
-    commands,
-    assets,
-    &tower_stats.tower[&tower_type].tower,
-    tower_type,
-    position,
-  );
+    .spawn(tower.clone())
+    .insert(tower.tower_type.get_sprite_sheet_bundle(assets, position))
+    .id()
 }
 
+#[allow(clippy::too_many_arguments)]
 fn tower_shooting(
   mut commands: Commands,
   assets: Res<GameAssets>, // Bullet assets
   mut towers: Query<(
     Entity,
     &mut Tower,
+    &mut TowerLifetimeStats,
     &TowerType,
     &mut Transform,
     &GlobalTransform,
   )>,
-  enemies: Query<(&GlobalTransform, &Enemy, &Movement)>,
-  time: Res<Time>,
+  enemies: Query<(Entity, &GlobalTransform, &Enemy, &Movement, &Path)>,
+  spatial_hash: Res<SpatialHash>,
+  map: Res<Map>,
+  fixed_time: Res<FixedTime>,
+  mut rng: ResMut<SimulationRng>,
 ) {
-  for (tower_entity, mut tower, tower_type, mut tower_transform, transform) in &mut towers {
+  // Opening a tower's UI gives it children and moves it to another archetype, so the query
+  // order changes between runs. Towers shoot in order of position instead, so random targets
+  // and bullets are handed out the same way in replays
+  let mut towers = towers.iter_mut().collect::<Vec<_>>();
+  towers.sort_by_key(|(.., transform, _)| {
+    (FloatOrd(transform.translation.x), FloatOrd(transform.translation.y))
+  });
+
+  for (tower_entity, mut tower, mut lifetime_stats, tower_type, mut tower_transform, transform) in
+    towers
+  {
     // Check if an enemy is in range so we can tick the timer
-    if enemy_in_range(&tower, &tower_transform, &enemies) {
+    if spatial_hash.any_within(tower_transform.translation, (tower.range + 50) as f32) {
       let bullet_spawn_pos = transform.translation() + tower.bullet_spawn_offset;
 
-      let direction =
-        get_enemy_direction(&enemies, bullet_spawn_pos, tower.range + 10, &tower.target);
+      let target = get_enemy_target(
+        &spatial_hash,
+        &enemies,
+        bullet_spawn_pos,
+        tower.range + 10,
+        &tower.target,
+        &mut rng,
+      );
+
+      // If there is an enemy in the tower's range (if target != None), then shoot bullet
+      if let Some((target, target_position)) = target {
+        let aim_position = match tower.projectile_mode {
+          ProjectileMode::Predictive => {
+            let (.., movement, path) = enemies.get(target).unwrap();
+            intercept_point(
+              bullet_spawn_pos,
+              tower.projectile_speed,
+              target_position,
+              movement.speed,
+              &map.checkpoints[path.id],
+              path.index,
+            )
+          }
+          _ => target_position,
+        };
+        let direction = aim_position - bullet_spawn_pos;
 
-      // If there is an enemy in the tower's range (if direction != None), then shoot bullet
-      if let Some(direction) = direction {
         // If the attack cooldown finished OR if there was no enemy spawned before, spawn bullet
         if tower.shooting_timer.just_finished() || tower.first_enemy_appeared {
           tower.first_enemy_appeared = false;
@@ -176,16 +225,18 @@ fn tower_shooting(
           tower_transform.rotation = Quat::from_rotation_z(angle);
 
           // Make bullet a child of tower
+          lifetime_stats.record_shot();
           commands.entity(tower_entity).with_children(|commands| {
             commands.spawn(tower_type.get_bullet(
-              tower.damage,
+              &tower,
+              target,
               &assets,
               Transform::from_translation(tower.bullet_spawn_offset),
             ));
           });
         }
 
-        tower.shooting_timer.tick(time.delta());
+        tower.shooting_timer.tick(fixed_time.period);
       }
     } else {
       tower.shooting_timer.reset();
@@ -194,22 +245,6 @@ fn tower_shooting(
   }
 }
 
-fn enemy_in_range(
-  tower: &Mut<Tower>,
-  tower_transform: &Mut<Transform>,
-  enemies: &Query<(&GlobalTransform, &Enemy, &Movement)>,
-) -> bool {
-  for (enemy_transform, ..) in enemies {
-    if Vec3::distance(tower_transform.translation, enemy_transform.translation())
-      <= (tower.range + 50) as f32
-    {
-      return true;
-    }
-  }
-
-  false
-}
-
 #[cfg(test)]
 #[path = "tower/tower_tests.rs"]
 mod tests;
\ No newline at end of file
@@ -12,6 +12,7 @@ fn test_bullet_initialization() {
     let bullet = Bullet {
         damage: 42,
         lifetime: Timer::from_seconds(2.0, TimerMode::Once),
+        ..default()
     };
 
     assert_eq!(bullet.damage, 42);
@@ -24,6 +25,7 @@ fn test_bullet_lifetime_partial_tick() {
     let mut bullet = Bullet {
         damage: 10,
         lifetime: Timer::from_seconds(1.0, TimerMode::Once),
+        ..default()
     };
     bullet.lifetime.tick(std::time::Duration::from_secs_f32(0.5));
     assert!(!bullet.lifetime.finished());
@@ -34,6 +36,7 @@ fn test_bullet_lifetime_exact_tick() {
     let mut bullet = Bullet {
         damage: 10,
         lifetime: Timer::from_seconds(1.0, TimerMode::Once),
+        ..default()
     };
     bullet.lifetime.tick(std::time::Duration::from_secs_f32(1.0));
     assert!(bullet.lifetime.finished());
@@ -44,6 +47,7 @@ fn test_bullet_lifetime_overtime_tick() {
     let mut bullet = Bullet {
         damage: 10,
         lifetime: Timer::from_seconds(1.0, TimerMode::Once),
+        ..default()
     };
     bullet.lifetime.tick(std::time::Duration::from_secs_f32(1.5));
     assert!(bullet.lifetime.finished());
@@ -92,4 +96,23 @@ fn test_tower_damage_exact_match() {
     }
 
     assert_eq!(total_damage, 50);
-}
\ No newline at end of file
+}
+#[test]
+fn test_bullet_spent_after_pierce_hits() {
+    let mut bullet = Bullet {
+        pierce: 2,
+        ..default()
+    };
+    assert!(!bullet.is_spent());
+    bullet.hit_enemies.push(Entity::from_raw(0));
+    assert!(!bullet.is_spent());
+    bullet.hit_enemies.push(Entity::from_raw(1));
+    assert!(bullet.is_spent());
+}
+
+#[test]
+fn test_bullet_without_pierce_hits_once() {
+    let mut bullet = Bullet::default();
+    bullet.hit_enemies.push(Entity::from_raw(0));
+    assert!(bullet.is_spent());
+}
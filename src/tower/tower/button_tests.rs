@@ -1,4 +1,5 @@
 use super::*;
+use crate::Tile;
 
 #[test]
 fn test_window_to_world_ndc_conversion_center() {
@@ -74,4 +75,57 @@ fn test_sprite_follower_overlap_detection() {
 
     assert!(Vec3::distance(follower_pos, tower_within_range) <= 50.0);
     assert!(Vec3::distance(follower_pos, tower_outside_range) > 50.0);
-}
\ No newline at end of file
+}
+#[test]
+fn test_towers_placeable_on_grass_only() {
+    for tower_type in TowerType::iter() {
+        assert!(tower_type.can_be_placed_on(&Tile::Grass));
+        assert!(!tower_type.can_be_placed_on(&Tile::Path(vec![0])));
+        assert!(!tower_type.can_be_placed_on(&Tile::Spawn));
+        assert!(!tower_type.can_be_placed_on(&Tile::End));
+        assert!(!tower_type.can_be_placed_on(&Tile::Empty));
+    }
+}
+
+#[test]
+fn test_only_ice_tower_placeable_on_water() {
+    assert!(TowerType::Ice.can_be_placed_on(&Tile::Water));
+    assert!(!TowerType::Fire.can_be_placed_on(&Tile::Water));
+    assert!(!TowerType::Nature.can_be_placed_on(&Tile::Water));
+}
+
+#[test]
+fn test_tile_allows_tower_at_world_position() {
+    let map = Map {
+        width: 3,
+        height: 1,
+        tiles: vec![vec![Tile::Grass, Tile::Path(vec![0]), Tile::Water]],
+        tile_size: 80,
+        checkpoints: vec![],
+    };
+    assert!(tile_allows_tower(&map, TowerType::Fire, Vec3::new(10.0, 0.0, 0.5)));
+    assert!(!tile_allows_tower(&map, TowerType::Fire, Vec3::new(80.0, 0.0, 0.5)));
+    assert!(!tile_allows_tower(&map, TowerType::Fire, Vec3::new(160.0, 0.0, 0.5)));
+    assert!(tile_allows_tower(&map, TowerType::Ice, Vec3::new(160.0, 0.0, 0.5)));
+    assert!(!tile_allows_tower(&map, TowerType::Ice, Vec3::new(400.0, 0.0, 0.5)));
+}
+
+#[test]
+fn test_can_place_tower_away_from_other_towers() {
+    let map = Map {
+        width: 2,
+        height: 1,
+        tiles: vec![vec![Tile::Grass, Tile::Grass]],
+        tile_size: 80,
+        checkpoints: vec![],
+    };
+    let towers = [Vec3::new(0.0, 0.0, 0.5)];
+    let can_place = |x: f32, towers: &[Vec3]| {
+        can_place_tower(&map, TowerType::Fire, Vec3::new(x, 0.0, 0.5), towers.iter().copied())
+    };
+
+    assert!(can_place(80.0, &towers));
+    assert!(!can_place(40.0, &towers));
+    assert!(can_place(40.0, &[]));
+    assert!(!can_place(-80.0, &[]));
+}
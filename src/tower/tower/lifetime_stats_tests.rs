@@ -0,0 +1,88 @@
+use super::*;
+
+#[test]
+fn test_accuracy() {
+    let mut stats = TowerLifetimeStats::default();
+    assert_eq!(stats.accuracy(), 0.);
+
+    for _ in 0..4 {
+        stats.record_shot();
+    }
+    stats.record_hit(EnemyType::Green, 1);
+    stats.record_hit(EnemyType::Green, 1);
+    stats.record_hit(EnemyType::Red, 1);
+    assert_eq!(stats.accuracy(), 75.);
+}
+
+#[test]
+fn test_damage_breakdown_sorted_by_damage() {
+    let mut stats = TowerLifetimeStats::default();
+    stats.record_hit(EnemyType::Green, 3);
+    stats.record_hit(EnemyType::Red, 5);
+    stats.record_hit(EnemyType::Green, 4);
+    stats.record_hit(EnemyType::Blue, 5);
+
+    assert_eq!(
+        stats.damage_breakdown(),
+        vec![(EnemyType::Green, 7), (EnemyType::Blue, 5), (EnemyType::Red, 5)]
+    );
+}
+
+#[test]
+fn test_damage_per_second_only_counts_recent_damage() {
+    let mut stats = TowerLifetimeStats::default();
+    stats.record_hit(EnemyType::Green, 10);
+    stats.tick(DPS_WINDOW - 1.);
+    stats.record_hit(EnemyType::Green, 5);
+    assert_eq!(stats.damage_per_second(), 15. / (DPS_WINDOW - 1.));
+
+    stats.tick(2.);
+    assert_eq!(stats.damage_per_second(), 5. / DPS_WINDOW);
+
+    stats.tick(DPS_WINDOW);
+    assert_eq!(stats.damage_per_second(), 0.);
+    assert_eq!(stats.damage_by_enemy_type[&EnemyType::Green], 15);
+}
+
+#[test]
+fn test_damage_per_second_of_new_tower() {
+    let mut stats = TowerLifetimeStats::default();
+    stats.record_hit(EnemyType::Green, 10);
+    assert_eq!(stats.damage_per_second(), 0.);
+
+    stats.tick(2.);
+    assert_eq!(stats.damage_per_second(), 5.);
+}
+
+#[test]
+fn test_format_damage_breakdown() {
+    let mut stats = TowerLifetimeStats::default();
+    assert_eq!(format_damage_breakdown(&stats, 2), "");
+
+    stats.record_hit(EnemyType::Red, 40);
+    stats.record_hit(EnemyType::Blue, 12);
+    stats.record_hit(EnemyType::Green, 3);
+    assert_eq!(format_damage_breakdown(&stats, 2), "Red: 40, Blue: 12");
+}
+
+#[test]
+fn test_tower_stats_file_starts_with_empty_lifetime_stats() {
+    let stats: TowerTypeStats =
+        ron::from_str(include_str!("../../../assets/data/stats.tower_stats.ron")).unwrap();
+    for bundle in stats.tower.values() {
+        assert_eq!(bundle.lifetime_stats.shots_fired, 0);
+        assert!(bundle.lifetime_stats.damage_by_enemy_type.is_empty());
+    }
+}
+
+#[test]
+fn test_indirect_damage_does_not_count_as_hit() {
+    let mut stats = TowerLifetimeStats::default();
+    stats.record_shot();
+    stats.record_hit(EnemyType::Green, 2);
+    stats.record_damage(EnemyType::Red, 2);
+    stats.record_damage(EnemyType::Red, 2);
+    assert_eq!(stats.shots_hit, 1);
+    assert_eq!(stats.accuracy(), 100.);
+    assert_eq!(stats.damage_breakdown(), vec![(EnemyType::Red, 4), (EnemyType::Green, 2)]);
+}
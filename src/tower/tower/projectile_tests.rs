@@ -0,0 +1,118 @@
+use super::*;
+
+fn enemies() -> Vec<(Entity, Vec3)> {
+    vec![
+        (Entity::from_raw(0), Vec3::new(0., 0., 0.)),
+        (Entity::from_raw(1), Vec3::new(30., 0., 0.)),
+        (Entity::from_raw(2), Vec3::new(0., 50., 0.)),
+        (Entity::from_raw(3), Vec3::new(100., 0., 0.)),
+    ]
+}
+
+#[test]
+fn test_splash_targets_within_radius() {
+    let targets = splash_targets(Vec3::ZERO, 50., Entity::from_raw(0), &enemies());
+    assert_eq!(targets, vec![Entity::from_raw(1), Entity::from_raw(2)]);
+}
+
+#[test]
+fn test_chain_jumps_to_closest_enemies() {
+    let targets = chain_targets(Vec3::ZERO, 2, 75., Entity::from_raw(0), &enemies());
+    assert_eq!(targets, vec![Entity::from_raw(1), Entity::from_raw(2)]);
+}
+
+#[test]
+fn test_chain_stops_when_out_of_range() {
+    let targets = chain_targets(Vec3::ZERO, 5, 40., Entity::from_raw(0), &enemies());
+    assert_eq!(targets, vec![Entity::from_raw(1)]);
+}
+
+#[test]
+fn test_projectile_effect_from_ron() {
+    let effect: ProjectileEffect = ron::from_str("Slow(speed_multiplier: 0.5, duration: 2.0)").unwrap();
+    assert_eq!(effect, ProjectileEffect::Slow { speed_multiplier: 0.5, duration: 2. });
+    let effect: ProjectileEffect = ron::from_str("ArmorPiercing").unwrap();
+    assert_eq!(effect, ProjectileEffect::ArmorPiercing);
+}
+
+#[test]
+fn test_tower_stats_file_gives_each_wizard_a_mechanic() {
+    use crate::{TowerType, TowerTypeStats};
+
+    let stats: TowerTypeStats =
+        ron::from_str(include_str!("../../../assets/data/stats.tower_stats.ron")).unwrap();
+    let projectile = |tower_type: TowerType| stats.tower[&tower_type].tower.projectile;
+
+    assert!(matches!(projectile(TowerType::Fire), ProjectileEffect::Splash { .. }));
+    assert!(matches!(projectile(TowerType::Ice), ProjectileEffect::Slow { .. }));
+    assert!(matches!(projectile(TowerType::Nature), ProjectileEffect::Poison { .. }));
+    assert_eq!(projectile(TowerType::Dark), ProjectileEffect::ArmorPiercing);
+    assert!(matches!(projectile(TowerType::Mage), ProjectileEffect::Chain { .. }));
+    assert_eq!(projectile(TowerType::Archmage), ProjectileEffect::None);
+    assert!(stats.tower[&TowerType::Archmage].tower.pierce > 1);
+}
+
+fn corner_path() -> Vec<Vec3> {
+    vec![Vec3::new(0., 0., 0.), Vec3::new(100., 0., 0.), Vec3::new(100., 100., 0.)]
+}
+
+#[test]
+fn test_predict_position_on_straight_segment() {
+    let position = predict_position(Vec3::ZERO, 50., &corner_path(), 1, 1.);
+    assert_eq!(position, Vec3::new(50., 0., 0.));
+}
+
+#[test]
+fn test_predict_position_around_corner() {
+    let position = predict_position(Vec3::new(80., 0., 0.), 50., &corner_path(), 1, 1.);
+    assert!(position.distance(Vec3::new(100., 30., 0.)) < 0.001);
+}
+
+#[test]
+fn test_predict_position_stops_at_end_of_path() {
+    let position = predict_position(Vec3::ZERO, 50., &corner_path(), 1, 100.);
+    assert_eq!(position, Vec3::new(100., 100., 0.));
+}
+
+#[test]
+fn test_intercept_point_meets_enemy() {
+    let origin = Vec3::new(150., 50., 0.);
+    let enemy = Vec3::new(90., 0., 0.);
+    let speed = 400.;
+    let projectile_speed = 1500.;
+
+    let intercept = intercept_point(origin, projectile_speed, enemy, speed, &corner_path(), 1);
+    let bullet_time = origin.distance(intercept) / projectile_speed;
+    let enemy_at = predict_position(enemy, speed, &corner_path(), 1, bullet_time);
+
+    // Enemy turns the corner before the bullet arrives
+    assert!(intercept.y > 0.);
+    assert!(intercept.distance(enemy_at) < 0.5);
+}
+
+#[test]
+fn test_intercept_point_of_still_enemy_is_its_position() {
+    let enemy = Vec3::new(40., 0., 0.);
+    let intercept = intercept_point(Vec3::new(0., 100., 0.), 1500., enemy, 0., &corner_path(), 1);
+    assert_eq!(intercept, enemy);
+}
+
+#[test]
+fn test_projectile_mode_from_ron() {
+    let mode: ProjectileMode = ron::from_str("Homing").unwrap();
+    assert_eq!(mode, ProjectileMode::Homing);
+    assert_eq!(ProjectileMode::default(), ProjectileMode::Straight);
+}
+
+#[test]
+fn test_tower_stats_file_sets_projectile_modes() {
+    use crate::{TowerType, TowerTypeStats};
+
+    let stats: TowerTypeStats =
+        ron::from_str(include_str!("../../../assets/data/stats.tower_stats.ron")).unwrap();
+    let mode = |tower_type: TowerType| stats.tower[&tower_type].tower.projectile_mode;
+
+    assert_eq!(mode(TowerType::Mage), ProjectileMode::Homing);
+    assert_eq!(mode(TowerType::Archmage), ProjectileMode::Predictive);
+    assert_eq!(mode(TowerType::Fire), ProjectileMode::Straight);
+}
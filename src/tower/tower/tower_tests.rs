@@ -91,4 +91,25 @@ fn test_tower_range_detection() {
 
     assert!(tower_pos.distance(enemy_in_range) <= range);
     assert!(tower_pos.distance(enemy_out_of_range) > range);
-}
\ No newline at end of file
+}
+#[test]
+fn test_tower_new_pierce_and_projectile_speed() {
+    let tower = Tower::new(Vec3::ZERO, 10, 1.0, 50, 100);
+    assert_eq!(tower.pierce, DEFAULT_PIERCE);
+    assert_eq!(tower.projectile_speed, DEFAULT_PROJECTILE_SPEED);
+}
+
+#[test]
+fn test_upgrades_file_modifies_pierce_and_projectile_speed() {
+    let upgrades: Upgrades =
+        ron::from_str(include_str!("../../../assets/data/tower.upgrades.ron")).unwrap();
+    let stats = upgrades
+        .upgrades
+        .values()
+        .flatten()
+        .flatten()
+        .flat_map(|upgrade| upgrade.upgrade.keys())
+        .collect::<Vec<_>>();
+    assert!(stats.contains(&&TowerStat::Pierce));
+    assert!(stats.contains(&&TowerStat::ProjectileSpeed));
+}
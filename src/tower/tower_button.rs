@@ -4,7 +4,9 @@ use strum::IntoEnumIterator;
 
 use crate::assets::*;
 use crate::tower::*;
-use crate::{GameData, GameState, GameplayUIRoot, MainCamera, Player};
+use crate::{
+  GameData, GameState, GameplaySet, GameplayUIRoot, MainCamera, Map, Player, PlayerCommand,
+};
 
 pub struct TowerButtonPlugin;
 
@@ -18,7 +20,7 @@ impl Plugin for TowerButtonPlugin {
           place_tower,
           lock_tower_buttons.after(generate_ui),
         )
-          .in_set(OnUpdate(GameState::Gameplay)),
+          .in_set(GameplaySet::Input),
       );
   }
 }
@@ -82,6 +84,27 @@ pub fn window_to_world_pos(
   world_pos
 }
 
+// Whether the tile under a world position allows the tower type to be placed on it
+pub fn tile_allows_tower(map: &Map, tower_type: TowerType, position: Vec3) -> bool {
+  map
+    .tile_at(position)
+    .is_some_and(|tile| tower_type.can_be_placed_on(tile))
+}
+
+// Closest a new tower can be placed to an existing one
+pub const TOWER_SPACING: f32 = 40.;
+
+// Whether a tower can be placed at a world position, given the positions of the placed towers
+pub fn can_place_tower(
+  map: &Map,
+  tower_type: TowerType,
+  position: Vec3,
+  mut towers: impl Iterator<Item = Vec3>,
+) -> bool {
+  tile_allows_tower(map, tower_type, position)
+    && towers.all(|tower| Vec3::distance(position, tower) > TOWER_SPACING)
+}
+
 #[cfg(test)]
 #[path = "tower/button_tests.rs"]
 mod tests;
@@ -123,27 +146,26 @@ fn place_tower(
     With<SpriteFollower>,
   >,
   assets: Res<GameAssets>,
-  mouse: Res<Input<MouseButton>>,
-  keys: Res<Input<KeyCode>>,
+  (mouse, keys): (Res<Input<MouseButton>>, Res<Input<KeyCode>>),
   windows: Query<&Window>,
   camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
-  mut player: Query<&mut Player>,
+  player: Query<&Player>,
   towers: Query<&Transform, (With<Tower>, Without<SpriteFollower>)>,
   mut clicked_tower: Query<Entity, With<TowerUpgradeUI>>,
-  mut meshes: ResMut<Assets<Mesh>>,
-  mut materials: ResMut<Assets<ColorMaterial>>,
+  (mut meshes, mut materials): (ResMut<Assets<Mesh>>, ResMut<Assets<ColorMaterial>>),
   game_data: Res<GameData>,
   tower_stats: Res<Assets<TowerTypeStats>>,
   node_query: Query<(&Node, &GlobalTransform, &Visibility), With<GameplayUIRoot>>,
-  //tilemap: Res<Map>,
+  map: Res<Map>,
   mut cursor_exited_ui: ResMut<CursorExitedUI>, // Flag to check initial mouse exit from button UI
+  mut player_commands: EventWriter<PlayerCommand>,
 ) {
   let Some(tower_stats) = tower_stats.get(&game_data.tower_type_stats)
     else { return; };
 
   let window = windows.get_single().unwrap();
   let (camera, camera_transform) = camera_query.single();
-  let mut player = player.single_mut();
+  let player = player.single();
 
   for (entity, mut transform, tower_type, mut color) in query.iter_mut() {
     if !clicked_tower.is_empty() {
@@ -159,41 +181,13 @@ fn place_tower(
 
       transform.translation = window_to_world_pos(window, position, camera, camera_transform);
 
-      // Tower range becomes red when trying to place on path/invalid tile
-      // for (y, row) in tilemap.tiles.iter().enumerate() {
-      //   let mouse_on_path = row.iter().enumerate().filter(|(x, tile)| {
-      //     if matches!(tile, Tile::Path(_)) &&
-      //       position.x >= (*x as f32 * tilemap.tile_size as f32 -
-      //        tilemap.tile_size as f32 / 2.) as f32 &&
-      //       position.y >= (y as f32 * tilemap.tile_size as f32 -
-      //        tilemap.tile_size as f32 / 2.) as f32 &&
-      //       position.x <= (*x as f32 * tilemap.tile_size as f32 +
-      //        tilemap.tile_size as f32 / 2.) as f32 &&
-      //       position.y <= (y as f32 * tilemap.tile_size as f32 +
-      //        tilemap.tile_size as f32 / 2.) as f32 {
-      //       println!("{:?}, {:?}, {:?}", position, x, y);
-      //       return true;
-      //     }
-      //     false
-      //   }).last();
-      //   if mouse_on_path.is_some() {
-      //     println!("ON PATH");
-      //     *color = materials.add(ColorMaterial::from(
-      //       Color::rgba_u8(202, 0, 0, 150)));
-      //   }
-      // }
-
-      let mouse_on_placed_tower = towers
-        .iter()
-        .filter(|tower_transform| {
-          Vec3::distance(transform.translation, tower_transform.translation) <= 50.
-        })
-        .last();
-
-      if mouse_on_placed_tower.is_some() {
-        *color = materials.add(ColorMaterial::from(Color::rgba_u8(202, 0, 0, 150)));
-      } else {
+      // Tower range becomes red when trying to place on another tower or an invalid tile,
+      // with the same check as placing it
+      let existing = towers.iter().map(|tower_transform| tower_transform.translation);
+      if can_place_tower(&map, *tower_type, transform.translation, existing) {
         *color = materials.add(ColorMaterial::from(Color::rgba_u8(0, 0, 0, 85)));
+      } else {
+        *color = materials.add(ColorMaterial::from(Color::rgba_u8(202, 0, 0, 150)));
       }
     }
 
@@ -203,25 +197,13 @@ fn place_tower(
         cursor_exited_ui.0 = false;
         let mouse_click_pos = window_to_world_pos(window, screen_pos, camera, camera_transform);
 
-        let mut place_tower = true;
-
-        for tower_transform in towers.iter() {
-          if Vec3::distance(mouse_click_pos, tower_transform.translation) <= 40. {
-            place_tower = false;
-          }
-        }
-        if place_tower {
-          player.money -= tower_stats.tower[tower_type].tower.price as usize;
+        let existing = towers.iter().map(|tower_transform| tower_transform.translation);
+        if can_place_tower(&map, *tower_type, mouse_click_pos, existing) {
           commands.entity(entity).despawn_recursive();
-          spawn_tower(
-            &mut commands,
-            *tower_type,
-            &assets,
-            mouse_click_pos,
-            &mut meshes,
-            &mut materials,
-            tower_stats,
-          );
+          player_commands.send(PlayerCommand::PlaceTower {
+            tower_type: *tower_type,
+            position: mouse_click_pos,
+          });
         }
       }
     }
@@ -244,7 +226,7 @@ fn place_tower(
       tower_spawn_from_keyboard_input(
         &mut commands,
         &keys,
-        &player,
+        player,
         window,
         camera,
         camera_transform,
@@ -257,6 +239,34 @@ fn place_tower(
   }
 }
 
+// Pay for a tower, spawn it and select it
+#[allow(clippy::too_many_arguments)]
+pub fn buy_tower(
+  commands: &mut Commands,
+  tower_type: TowerType,
+  position: Vec3,
+  player: &mut Player,
+  assets: &GameAssets,
+  meshes: &mut Assets<Mesh>,
+  materials: &mut Assets<ColorMaterial>,
+  tower_stats: &TowerTypeStats,
+) {
+  let tower = tower_type.get_tower(tower_stats);
+  player.money -= tower.tower.price as usize;
+
+  let tower_entity = spawn_tower(commands, &tower, assets, position);
+  select_tower(
+    commands,
+    tower_entity,
+    &tower.tower,
+    tower_type,
+    position,
+    assets,
+    meshes,
+    materials,
+  );
+}
+
 fn spawn_sprite_follower(
   commands: &mut Commands,
   window: &Window,
@@ -0,0 +1,148 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::enemy::*;
+use crate::tower::*;
+use crate::{GameplaySet, SimulationEvents};
+
+pub struct TowerLifetimeStatsPlugin;
+
+impl Plugin for TowerLifetimeStatsPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .register_type::<TowerLifetimeStats>()
+      .add_simulation_event::<TowerDamageEvent>()
+      .add_systems(
+        (record_tower_damage, record_tower_kills, tick_tower_lifetime_stats)
+          .in_set(GameplaySet::Simulation)
+          .in_schedule(CoreSchedule::FixedUpdate),
+      );
+  }
+}
+
+// Damage per second is measured over this many seconds
+pub const DPS_WINDOW: f32 = 5.;
+
+// Sent for every bit of damage a tower deals to an enemy
+pub struct TowerDamageEvent {
+  pub tower: Entity,
+  pub enemy_type: EnemyType,
+  // Damage actually taken off the enemy's health
+  pub damage: u32,
+  // Whether the bullet itself hit the enemy. Splash, chain and poison damage aren't direct hits
+  pub direct_hit: bool,
+}
+
+#[derive(Component, Reflect, Default, Clone, Serialize, Deserialize)]
+#[reflect(Component)]
+#[serde(default)]
+pub struct TowerLifetimeStats {
+  pub kills: u32,
+  pub damage_by_enemy_type: HashMap<EnemyType, u32>,
+  pub shots_fired: u32,
+  pub shots_hit: u32,
+  // Time the tower has existed, in game time
+  pub elapsed: f32,
+  // Time and amount of damage dealt during the last `DPS_WINDOW` seconds
+  #[reflect(ignore)]
+  pub recent_damage: VecDeque<(f32, u32)>,
+}
+
+impl TowerLifetimeStats {
+  pub fn record_shot(&mut self) {
+    self.shots_fired += 1;
+  }
+
+  pub fn record_hit(&mut self, enemy_type: EnemyType, damage: u32) {
+    self.shots_hit += 1;
+    self.record_damage(enemy_type, damage);
+  }
+
+  // Damage that doesn't count as a bullet hitting
+  pub fn record_damage(&mut self, enemy_type: EnemyType, damage: u32) {
+    *self.damage_by_enemy_type.entry(enemy_type).or_default() += damage;
+    self.recent_damage.push_back((self.elapsed, damage));
+  }
+
+  pub fn tick(&mut self, delta_seconds: f32) {
+    self.elapsed += delta_seconds;
+    while let Some((time, _)) = self.recent_damage.front() {
+      if self.elapsed - time <= DPS_WINDOW {
+        break;
+      }
+      self.recent_damage.pop_front();
+    }
+  }
+
+  // Percentage of bullets that hit an enemy
+  pub fn accuracy(&self) -> f32 {
+    if self.shots_fired == 0 {
+      return 0.;
+    }
+    (self.shots_hit.min(self.shots_fired) as f32 / self.shots_fired as f32) * 100.
+  }
+
+  // Towers younger than `DPS_WINDOW` are measured over the time they have existed
+  pub fn damage_per_second(&self) -> f32 {
+    let window = self.elapsed.min(DPS_WINDOW);
+    if window <= 0. {
+      return 0.;
+    }
+    let damage: u32 = self.recent_damage.iter().map(|(_, damage)| damage).sum();
+    damage as f32 / window
+  }
+
+  // Enemy types sorted from most to least damaged
+  pub fn damage_breakdown(&self) -> Vec<(EnemyType, u32)> {
+    let mut breakdown = self
+      .damage_by_enemy_type
+      .iter()
+      .map(|(enemy_type, damage)| (*enemy_type, *damage))
+      .collect::<Vec<_>>();
+    breakdown.sort_by_key(|(enemy_type, damage)| (std::cmp::Reverse(*damage), *enemy_type as u8));
+    breakdown
+  }
+}
+
+fn record_tower_damage(
+  mut towers: Query<(&mut Tower, &mut TowerLifetimeStats)>,
+  mut damage_events: EventReader<TowerDamageEvent>,
+) {
+  for hit in damage_events.iter() {
+    // The tower may have been sold while its bullet was still flying
+    if let Ok((mut tower, mut stats)) = towers.get_mut(hit.tower) {
+      tower.total_damage += hit.damage;
+      if hit.direct_hit {
+        stats.record_hit(hit.enemy_type, hit.damage);
+      } else {
+        stats.record_damage(hit.enemy_type, hit.damage);
+      }
+    }
+  }
+}
+
+fn record_tower_kills(
+  mut towers: Query<&mut TowerLifetimeStats>,
+  mut death_events: EventReader<EnemyDeathEvent>,
+) {
+  for death in death_events.iter() {
+    if let Some(mut stats) = death.killer.and_then(|killer| towers.get_mut(killer).ok()) {
+      stats.kills += 1;
+    }
+  }
+}
+
+fn tick_tower_lifetime_stats(
+  mut towers: Query<&mut TowerLifetimeStats>,
+  fixed_time: Res<FixedTime>,
+) {
+  for mut stats in &mut towers {
+    stats.tick(fixed_time.period.as_secs_f32());
+  }
+}
+
+#[cfg(test)]
+#[path = "tower/lifetime_stats_tests.rs"]
+mod tests;
@@ -3,13 +3,13 @@ use bevy::sprite::Mesh2dHandle;
 
 use crate::assets::*;
 use crate::tower::*;
-use crate::{GameData, GameState, MainCamera, Player};
+use crate::{GameplaySet, MainCamera, Player, PlayerCommand};
 
 pub struct TowerSelectionPlugin;
 
 impl Plugin for TowerSelectionPlugin {
   fn build(&self, app: &mut App) {
-    app.add_systems((mouse_click, tower_ui_interaction).in_set(OnUpdate(GameState::Gameplay)));
+    app.add_systems((mouse_click, tower_ui_interaction).in_set(GameplaySet::Input));
   }
 }
 
@@ -76,30 +76,49 @@ fn mouse_click_interaction(
       if Vec3::distance(mouse_click_pos, transform.translation) <= 25.
         && !cursor_above_ui(window, node_query)
       {
-        commands.entity(tower_entity).with_children(|commands| {
-          commands
-            .spawn(spawn_tower_range(meshes, materials, tower.range))
-            .insert(Name::new("Tower Range"))
-            .insert(TowerUpgradeUI);
-        });
-
-        spawn_tower_ui(commands, assets, tower, *tower_type, transform.translation);
+        select_tower(
+          commands,
+          tower_entity,
+          tower,
+          *tower_type,
+          transform.translation,
+          assets,
+          meshes,
+          materials,
+        );
       }
     }
   }
 }
 
+// Show the tower's range and its UI - Targeting priority, Selling & Upgrades
+#[allow(clippy::too_many_arguments)]
+pub fn select_tower(
+  commands: &mut Commands,
+  tower_entity: Entity,
+  tower: &Tower,
+  tower_type: TowerType,
+  position: Vec3,
+  assets: &GameAssets,
+  meshes: &mut Assets<Mesh>,
+  materials: &mut Assets<ColorMaterial>,
+) {
+  commands.entity(tower_entity).with_children(|commands| {
+    commands
+      .spawn(spawn_tower_range(meshes, materials, tower.range))
+      .insert(Name::new("Tower Range"))
+      .insert(TowerUpgradeUI);
+  });
+
+  spawn_tower_ui(commands, assets, tower, tower_type, position);
+}
+
 fn tower_ui_interaction(
   //assets: Res<GameAssets>,
-  mut commands: Commands,
-  mut towers: Query<(Entity, &mut Tower, &TowerType, &Children)>,
+  towers: Query<(&Tower, &Transform, &Children)>,
   clicked_tower: Query<Entity, With<TowerUpgradeUI>>,
   keys: Res<Input<KeyCode>>,
-  mut player: Query<&mut Player>,
-  game_data: Res<GameData>,
-  upgrades: Res<Assets<Upgrades>>,
-  mut meshes: ResMut<Assets<Mesh>>,
-  mut tower_range_radius: Query<&mut Mesh2dHandle>,
+  mut player_commands: EventWriter<PlayerCommand>,
   // UI Buttons
   //mut images: Query<(&mut UiImage, With<SellButton>)>,
   prev_target_button_interaction: Query<
@@ -127,26 +146,17 @@ fn tower_ui_interaction(
     (Changed<Interaction>, With<Button>),
   >,
 ) {
-  let Some(upgrades) = upgrades.get(&game_data.tower_upgrades)
-    else { return; };
-
   if !clicked_tower.is_empty() {
-    let mut player = player.single_mut();
-
     // Keyboard shortcuts
-    for (entity, mut tower, tower_type, children) in towers.iter_mut() {
+    for (tower, transform, children) in towers.iter() {
       for _ in clicked_tower.iter_many(children) {
         let mut upgrade_path_index: Option<usize> = None;
+        let mut sell = false;
+        let mut target = tower.target.clone();
 
         // Sell tower
         if keys.just_pressed(KeyCode::Back) {
-          // Despawn tower
-          commands.entity(entity).despawn_recursive();
-          // Despawn UI
-          for entity in clicked_tower.iter() {
-            commands.entity(entity).despawn_recursive();
-          }
-          player.money += (tower.total_spent / 3) as usize;
+          sell = true;
         }
         // Upgrade tower - Path 1
         else if keys.just_pressed(KeyCode::Comma) {
@@ -164,27 +174,11 @@ fn tower_ui_interaction(
         else if (keys.pressed(KeyCode::LControl) || keys.pressed(KeyCode::RControl))
           && keys.just_pressed(KeyCode::Tab)
         {
-          tower.target.prev_target();
+          target.prev_target();
         }
         // Change targeting priority (right)
         else if keys.just_pressed(KeyCode::Tab) {
-          tower.target.next_target();
-        }
-
-        // Upgrade
-        if let Some(path_index) = upgrade_path_index {
-          let i = tower.upgrades.upgrades[path_index];
-          let tower_upgrades = &upgrades.upgrades[tower_type][path_index];
-
-          if i < tower_upgrades.len() && player.money >= tower_upgrades[i].cost {
-            player.money -= tower_upgrades[i].cost;
-            tower.upgrade(
-              &tower_upgrades[i],
-              path_index,
-              &mut meshes,
-              &mut tower_range_radius,
-            );
-          }
+          target.next_target();
         }
 
         // Button interaction
@@ -197,7 +191,7 @@ fn tower_ui_interaction(
               // for (mut image) in images.iter_mut() {
               // }
 
-              tower.target.prev_target();
+              target.prev_target();
             }
             Interaction::Hovered => {
               // Change button UI !!!
@@ -215,7 +209,7 @@ fn tower_ui_interaction(
               // Change button UI
               // for (mut image) in images.iter_mut() {
               // }
-              tower.target.next_target();
+              target.next_target();
             }
             Interaction::Hovered => {
               // Change button UI !!!
@@ -233,14 +227,7 @@ fn tower_ui_interaction(
               // Change button UI
               // for (mut image) in images.iter_mut() {
               // }
-
-              // Despawn tower
-              commands.entity(entity).despawn_recursive();
-              // Despawn UI
-              for entity in clicked_tower.iter() {
-                commands.entity(entity).despawn_recursive();
-              }
-              player.money += (tower.total_spent / 3) as usize;
+              sell = true;
             }
             Interaction::Hovered => {
               // Change button UI !!!
@@ -253,38 +240,84 @@ fn tower_ui_interaction(
 
         // Upgrade buttons
         for (interaction, state) in &upgrade_button_interaction {
-          let i = tower.upgrades.upgrades[state.path_index];
-          let tower_upgrades = &upgrades.upgrades[tower_type][state.path_index];
-
-          if i < tower_upgrades.len() && player.money >= tower_upgrades[i].cost {
-            match interaction {
-              Interaction::Clicked => {
-                // Change button UI
-                // for (mut image) in images.iter_mut() {
-                // }
-
-                player.money -= tower_upgrades[i].cost;
-                tower.upgrade(
-                  &tower_upgrades[i],
-                  state.path_index,
-                  &mut meshes,
-                  &mut tower_range_radius,
-                );
-              }
-              Interaction::Hovered => {
-                // Change button UI !!!
-              }
-              Interaction::None => {
-                // Change button UI !!!
-              }
+          match interaction {
+            Interaction::Clicked => {
+              // Change button UI
+              // for (mut image) in images.iter_mut() {
+              // }
+              upgrade_path_index = Some(state.path_index);
+            }
+            Interaction::Hovered => {
+              // Change button UI !!!
+            }
+            Interaction::None => {
+              // Change button UI !!!
             }
           }
         }
+
+        if target != tower.target {
+          player_commands.send(PlayerCommand::SetTargeting {
+            tower: transform.translation,
+            target,
+          });
+        }
+
+        if let Some(path_index) = upgrade_path_index {
+          player_commands.send(PlayerCommand::Upgrade {
+            tower: transform.translation,
+            path_index,
+          });
+        }
+
+        if sell {
+          player_commands.send(PlayerCommand::Sell {
+            tower: transform.translation,
+          });
+        }
       }
     }
   }
 }
 
+// Buy the next upgrade of a path if there is one and the player can afford it
+pub fn upgrade_tower(
+  tower: &mut Tower,
+  tower_type: &TowerType,
+  path_index: usize,
+  player: &mut Player,
+  upgrades: &Upgrades,
+  meshes: &mut Assets<Mesh>,
+  tower_range_radius: &mut Query<&mut Mesh2dHandle>,
+) -> bool {
+  let i = tower.upgrades.upgrades[path_index];
+  let tower_upgrades = &upgrades.upgrades[tower_type][path_index];
+
+  if i >= tower_upgrades.len() || player.money < tower_upgrades[i].cost {
+    return false;
+  }
+  player.money -= tower_upgrades[i].cost;
+  tower.upgrade(&tower_upgrades[i], path_index, meshes, tower_range_radius);
+  true
+}
+
+// Despawn the tower and its UI, and refund a third of what was spent on it
+pub fn sell_tower(
+  commands: &mut Commands,
+  tower_entity: Entity,
+  tower: &Tower,
+  player: &mut Player,
+  tower_ui: impl Iterator<Item = Entity>,
+) {
+  // Despawn tower
+  commands.entity(tower_entity).despawn_recursive();
+  // Despawn UI
+  for entity in tower_ui {
+    commands.entity(entity).despawn_recursive();
+  }
+  player.money += (tower.total_spent / 3) as usize;
+}
+
 #[cfg(test)]
 #[path = "tower/selection_tests.rs"]
 mod tests;
\ No newline at end of file
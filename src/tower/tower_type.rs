@@ -7,6 +7,7 @@ use strum_macros::{Display, EnumIter};
 use crate::assets::*;
 use crate::movement::*;
 use crate::tower::*;
+use crate::Tile;
 
 #[derive(
   EnumIter, Component, Display, Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize,
@@ -27,6 +28,11 @@ pub struct TowerTypeStats {
 }
 
 impl TowerType {
+  // Every wizard stands on grass, the ice wizard can also freeze water to stand on
+  pub fn can_be_placed_on(&self, tile: &Tile) -> bool {
+    matches!((self, tile), (_, Tile::Grass) | (TowerType::Ice, Tile::Water))
+  }
+
   pub fn get_tower(&self, tower_stats: &TowerTypeStats) -> TowerBundle {
     tower_stats.tower[self].clone()
   }
@@ -48,86 +54,38 @@ impl TowerType {
     }
   }
 
-  pub fn get_bullet(&self, damage: u32, assets: &GameAssets, position: Transform) -> BulletBundle {
-    match self {
-      TowerType::Nature => BulletBundle {
-        bullet: Bullet {
-          damage,
-          lifetime: Timer::from_seconds(1.25, TimerMode::Once),
-        },
-        movement: Movement::new(Vec3::new(0.00000001, 0., 0.), 1500.),
-        sprite: SpriteBundle {
-          texture: assets.wizard_nature_bullet.clone(),
-          transform: position,
-          ..default()
-        },
-        name: Name::new("Bullet"),
-      },
-      TowerType::Fire => BulletBundle {
-        bullet: Bullet {
-          damage,
-          lifetime: Timer::from_seconds(1.25, TimerMode::Once),
-        },
-        movement: Movement::new(Vec3::new(0.00000001, 0., 0.), 1500.),
-        sprite: SpriteBundle {
-          texture: assets.wizard_fire_bullet.clone(),
-          transform: position,
-          ..default()
-        },
-        name: Name::new("Bullet"),
-      },
-      TowerType::Ice => BulletBundle {
-        bullet: Bullet {
-          damage,
-          lifetime: Timer::from_seconds(1.25, TimerMode::Once),
-        },
-        movement: Movement::new(Vec3::new(0.00000001, 0., 0.), 1500.),
-        sprite: SpriteBundle {
-          texture: assets.wizard_ice_bullet.clone(),
-          transform: position,
-          ..default()
-        },
-        name: Name::new("Bullet"),
-      },
-      TowerType::Dark => BulletBundle {
-        bullet: Bullet {
-          damage,
-          lifetime: Timer::from_seconds(1.25, TimerMode::Once),
-        },
-        movement: Movement::new(Vec3::new(0.00000001, 0., 0.), 1500.),
-        sprite: SpriteBundle {
-          texture: assets.wizard_dark_bullet.clone(),
-          transform: position,
-          ..default()
-        },
-        name: Name::new("Bullet"),
-      },
-      TowerType::Mage => BulletBundle {
-        bullet: Bullet {
-          damage,
-          lifetime: Timer::from_seconds(1.25, TimerMode::Once),
-        },
-        movement: Movement::new(Vec3::new(0.00000001, 0., 0.), 1500.),
-        sprite: SpriteBundle {
-          texture: assets.wizard_mage_bullet.clone(),
-          transform: position,
-          ..default()
-        },
-        name: Name::new("Bullet"),
+  pub fn get_bullet(
+    &self,
+    tower: &Tower,
+    target: Entity,
+    assets: &GameAssets,
+    position: Transform,
+  ) -> BulletBundle {
+    let texture = match self {
+      TowerType::Nature => assets.wizard_nature_bullet.clone(),
+      TowerType::Fire => assets.wizard_fire_bullet.clone(),
+      TowerType::Ice => assets.wizard_ice_bullet.clone(),
+      TowerType::Dark => assets.wizard_dark_bullet.clone(),
+      TowerType::Mage => assets.wizard_mage_bullet.clone(),
+      TowerType::Archmage => assets.wizard_archmage_bullet.clone(),
+    };
+
+    BulletBundle {
+      bullet: Bullet {
+        damage: tower.damage,
+        lifetime: Timer::from_seconds(1.25, TimerMode::Once),
+        effect: tower.projectile,
+        pierce: tower.pierce,
+        homing_target: matches!(tower.projectile_mode, ProjectileMode::Homing).then_some(target),
+        hit_enemies: vec![],
       },
-      TowerType::Archmage => BulletBundle {
-        bullet: Bullet {
-          damage,
-          lifetime: Timer::from_seconds(1.25, TimerMode::Once),
-        },
-        movement: Movement::new(Vec3::new(0.00000001, 0., 0.), 1500.),
-        sprite: SpriteBundle {
-          texture: assets.wizard_archmage_bullet.clone(),
-          transform: position,
-          ..default()
-        },
-        name: Name::new("Bullet"),
+      movement: Movement::new(Vec3::new(0.00000001, 0., 0.), tower.projectile_speed),
+      sprite: SpriteBundle {
+        texture,
+        transform: position,
+        ..default()
       },
+      name: Name::new("Bullet"),
     }
   }
-}
\ No newline at end of file
+}
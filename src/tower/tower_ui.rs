@@ -62,7 +62,7 @@ fn update_tower_ui(
   game_data: Res<GameData>,
   upgrades: Res<Assets<Upgrades>>,
   mut child_q: Query<&Parent, With<TowerUpgradeUI>>,
-  mut parent_q: Query<(&mut Tower, &TowerType)>,
+  mut parent_q: Query<(&mut Tower, &TowerLifetimeStats, &TowerType)>,
   mut stats_ui: Query<&mut Text, With<TowerStatsUI>>,
   mut lifetime_stats_ui: Query<&mut Text, (With<TowerLifetimeStatsUI>, Without<TowerStatsUI>)>,
   mut targeting_priority_ui: Query<
@@ -107,14 +107,14 @@ fn update_tower_ui(
     else { return; };
 
   for parent in child_q.iter_mut() {
-    let (tower, tower_type) = parent_q.get_mut(parent.get()).unwrap();
+    let (tower, tower_lifetime_stats, tower_type) = parent_q.get_mut(parent.get()).unwrap();
 
     // Update tower stats
     for mut stats in stats_ui.iter_mut() {
       *stats = Text::from_section(
         format!(
-          " Damage: {}\n Attack Speed: {:.2}\n Range: {}\n Pierce: \n Projectile Speed: ",
-          tower.damage, tower.attack_speed, tower.range
+          " Damage: {}\n Attack Speed: {:.2}\n Range: {}\n Pierce: {}\n Projectile Speed: {}",
+          tower.damage, tower.attack_speed, tower.range, tower.pierce, tower.projectile_speed
         ),
         stats.sections[0].style.clone(),
       );
@@ -124,8 +124,13 @@ fn update_tower_ui(
     for mut lifetime_stats in lifetime_stats_ui.iter_mut() {
       *lifetime_stats = Text::from_section(
         format!(
-          " Total Damage: {}  Total Spent: ${}",
-          tower.total_damage, tower.total_spent
+          " Total Damage: {}  Total Spent: ${}  Kills: {}\n Accuracy: {:.0}%  DPS: {:.1}  {}",
+          tower.total_damage,
+          tower.total_spent,
+          tower_lifetime_stats.kills,
+          tower_lifetime_stats.accuracy(),
+          tower_lifetime_stats.damage_per_second(),
+          format_damage_breakdown(tower_lifetime_stats, 2),
         ),
         lifetime_stats.sections[0].style.clone(),
       );
@@ -180,6 +185,10 @@ fn update_tower_ui(
             TowerStat::Damage => upgrade_stats_string += &format!("+ {} damage\n", v),
             TowerStat::AttackSpeed => upgrade_stats_string += &format!("- {}% attack speed\n", v),
             TowerStat::Range => upgrade_stats_string += &format!("+ {} range\n", v),
+            TowerStat::Pierce => upgrade_stats_string += &format!("+ {} pierce\n", v),
+            TowerStat::ProjectileSpeed => {
+              upgrade_stats_string += &format!("+ {} projectile speed\n", v)
+            }
           }
         }
 
@@ -192,6 +201,17 @@ fn update_tower_ui(
   }
 }
 
+// Most damaged enemy types - "Red: 40, Blue: 12"
+pub fn format_damage_breakdown(lifetime_stats: &TowerLifetimeStats, count: usize) -> String {
+  lifetime_stats
+    .damage_breakdown()
+    .iter()
+    .take(count)
+    .map(|(enemy_type, damage)| format!("{}: {}", enemy_type, damage))
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
 pub fn spawn_tower_range(
   meshes: &mut Assets<Mesh>,
   materials: &mut Assets<ColorMaterial>,
@@ -243,7 +263,7 @@ pub fn spawn_tower_ui(
           style: Style {
             size: Size::new(Val::Px(100.), Val::Px(100.)),
             margin: UiRect {
-              top: Val::Percent(5.),
+              top: Val::Percent(10.),
               left: Val::Percent(5.),
               right: Val::Percent(5.),
               ..default()
@@ -263,7 +283,7 @@ pub fn spawn_tower_ui(
           style: Style {
             size: Size::new(Val::Percent(50.), Val::Percent(35.)),
             align_items: AlignItems::Center,
-            margin: UiRect::top(Val::Percent(2.5)),
+            margin: UiRect::top(Val::Percent(10.)),
             ..default()
           },
           ..default()
@@ -288,12 +308,12 @@ pub fn spawn_tower_ui(
         .insert(TowerUI)
         .insert(Name::new("TowerStats"));
 
-      // Total damage and total spent
+      // Lifetime stats - damage, money spent, kills, accuracy and damage per second
       commands
         .spawn(NodeBundle {
           background_color: BackgroundColor(Color::RED),
           style: Style {
-            size: Size::new(Val::Percent(100.), Val::Percent(4.5)),
+            size: Size::new(Val::Percent(100.), Val::Percent(9.)),
             position_type: PositionType::Absolute,
             ..default()
           },